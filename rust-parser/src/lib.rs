@@ -1,286 +1,5403 @@
+// pyo3 0.22 매크로가 PyResult 반환 함수에 불필요한 into()를 생성하므로 허용
+#![allow(clippy::useless_conversion)]
+
 use pyo3::prelude::*;
-use pyo3::types::PyDict;
-use regex::Regex;
+use pyo3::types::{PyBytes, PyDict, PyList};
+use regex::{Regex, RegexBuilder};
+use encoding_rs::Encoding;
 use once_cell::sync::Lazy;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+// 정규식 패턴들을 한 번만 컴파일 (성능 최적화)
+// 소수부는 밀리초(3자리), -v usec(6자리), -v nsec(9자리)
+static TIME_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(\d{2}-\d{2}\s+\d{2}:\d{2}:\d{2}\.\d{3}(?:\d{3}){0,2})").unwrap()
+});
+
+static THREADTIME_SIMPLE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\d+)\s+-\s+-(?:\s+(.*))?$").unwrap()
+});
+
+static THREADTIME_COMPLEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^([VDIWEAF])\s+-\s+-\s+(\d+)\s+(\d+)\s+([VDIWEAF])(?:\s+(.*))?$").unwrap()
+});
+
+static LEVEL_TAG_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^([DIWEFV])/([^(]+)\(\s*([^)]*?)\s*\)\s+(.*)$").unwrap()
+});
+
+// logcat -v threadtime,uid: UID는 숫자 또는 이름 (1000, system, u0_a123)
+static UID_THREADTIME_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\S+)\s+(\d+)\s+(\d+)\s+([VDIWEAF])(?:\s+(.*))?$").unwrap()
+});
+
+// logcat -v epoch: 1700000000.123  PID  TID  L  Tag: Message (초.소수부, 1970년 기준)
+static EPOCH_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\d{9,11}\.\d{3}(?:\d{3}){0,2})\s+(\d+)\s+(\d+)\s+([VDIWEAF])(?:\s+(.*))?$").unwrap()
+});
+
+// logcat -v brief: I/Tag( 1234): message (타임스탬프 없음)
+static BRIEF_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^([VDIWEAF])/([^(]*?)\s*\(\s*(\d+)\s*\):\s?(.*)$").unwrap()
+});
+
+// logcat -v process: I( 1234) message  (Tag)
+static PROCESS_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^([VDIWEAF])\(\s*(\d+)\s*\)\s(.*?)\s*\(([^()]+)\)$").unwrap()
+});
+
+// logcat -v tag: I/Tag: message (PID 없음, "Tag: message"는 split_tag_message로 나눔)
+static TAG_ONLY_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^([VDIWEAF])/(.*:.*)$").unwrap()
+});
+
+// 메시지 안에 들어 있는 brief 형식 줄: I/Tag( 1234): message
+static NESTED_BRIEF_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?:^|\s)([VDIWEAF])/([^\s(:][^(:]*?)\s*\(\s*(\d+)\s*\):\s*(.*)$").unwrap()
+});
+
+static INIT_START_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^[Ss]tarting service '([^']+)'").unwrap()
+});
+
+static INIT_EXIT_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^Service '([^']+)' \(pid (\d+)\) (exited with status|received signal|killed by signal) (-?\d+)").unwrap()
+});
+
+// ART/Dalvik 시작 비용 로그
+// "Verification of void com.foo.Bar.init() took 123.456ms"
+// "JIT compiled void com.foo.Bar.run() in 12.5ms"
+// "Class com.foo.Bar failed lock verification and will run slower."
+static JIT_VERIFY_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"Verification of (.+?) took ([\d.]+)(ns|us|ms|s)\b").unwrap()
+});
+
+static JIT_COMPILED_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"JIT compiled (.+?)(?:\s+(?:in|took)\s+([\d.]+)(ns|us|ms|s))?\s*$").unwrap()
+});
+
+static JIT_LOCK_VERIFY_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"Class (\S+) failed lock verification").unwrap()
+});
+
+// ActivityManager의 프로세스 시작 로그: "Start proc 1309:com.android.systemui/u0a194 for service {...}"
+static START_PROC_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^Start proc (\d+):([^/\s]+)").unwrap()
+});
+
+// dumpstate 섹션 구분선: "------ MEMORY INFO (/proc/meminfo) ------", "----- dumpsys activity -----"
+static DUMPSYS_HEADER_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^-{5,}\s+(.+?)\s+-{5,}$").unwrap()
+});
+
+// 섹션 종료 줄: "------ 0.012s was the duration of 'MEMORY INFO' ------",
+// "--------- 0.003s was the duration of dumpsys SurfaceFlinger, ending at: ..."
+static DUMPSYS_DURATION_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^-+\s*[\d.]+s was the duration of (?:'([^']+)'|dumpsys ([^,\s]+))").unwrap()
+});
+
+// dumpsys 서비스 시작: "DUMP OF SERVICE activity:", "DUMP OF SERVICE CRITICAL SurfaceFlinger:"
+static DUMP_OF_SERVICE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^DUMP OF SERVICE (?:CRITICAL |HIGH |NORMAL )?(\S+?):?$").unwrap()
+});
+
+// 16진수(0x..)와 10진수 숫자열 - 메시지 정규화 시 '#'로 마스킹
+static NUMBER_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"0[xX][0-9a-fA-F]+|\d+").unwrap()
+});
+
+static BINDER_FAIL_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)binder transaction failure|failed binder transaction|failed to transact|transaction_failed|failed_transaction|transaction failed").unwrap()
+});
+
+// binder 에러 코드: "error: -28", "failed 29189/-22", "failed to transact: -32"
+static BINDER_ERROR_CODE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(?:error:?\s*|\d/|transact:\s*)(-\d+)").unwrap()
+});
+
+// 스택 트레이스 연속 줄: "at com.foo.Bar(Bar.java:12)", "Caused by: ...", "... 5 more"
+static CONTINUATION_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(?:at\s|Caused by:|Suppressed:|\.\.\.\s*\d+\s+more)").unwrap()
+});
+
+static DISPLAY_PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
+    vec![
+        Regex::new(r"(?i)displayId[:\s]+(\d+)").unwrap(),
+        Regex::new(r"(?i)display[:\s]+(\d+)").unwrap(),
+        Regex::new(r"(?i)Display\s+(\d+)").unwrap(),
+    ]
+});
+
+/// 정규식으로 추출한 한 줄의 필드 (원본 라인을 빌려 씀, GIL 불필요)
+/// Python 없이 비교할 수 있도록 Debug/PartialEq 구현 (Rust 쪽 테스트·벤치마크용)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct LogFields<'a> {
+    /// 앞뒤 공백을 뺀 원본 줄
+    line: &'a str,
+    timestamp: &'a str,
+    level: &'a str,
+    pid: &'a str,
+    tid: &'a str,
+    tag: &'a str,
+    message: &'a str,
+    /// -v uid 형식일 때만 존재
+    uid: Option<&'a str>,
+    /// 사용자 형식으로 파싱됐으면 CUSTOM_FORMATS 인덱스
+    custom_format: Option<usize>,
+}
+
+/// 형식 6~8 (-v brief / process / tag, 타임스탬프 없음 → "-")
+fn match_untimed_fields(line: &str, formats: LogFormats) -> Option<LogFields<'_>> {
+    // 형식 6: Level/Tag( PID): Message
+    if let Some(caps) = formats.has(LogFormats::BRIEF).then(|| BRIEF_PATTERN.captures(line)).flatten() {
+        return Some(LogFields {
+            line,
+            timestamp: "-",
+            level: caps.get(1)?.as_str(),
+            pid: caps.get(3)?.as_str(),
+            tid: "-",
+            tag: caps.get(2)?.as_str().trim(),
+            message: caps.get(4)?.as_str().trim(),
+            uid: None,
+            custom_format: None,
+        });
+    }
+
+    // 형식 7: Level( PID) Message  (Tag)
+    if let Some(caps) = formats.has(LogFormats::PROCESS).then(|| PROCESS_PATTERN.captures(line)).flatten() {
+        return Some(LogFields {
+            line,
+            timestamp: "-",
+            level: caps.get(1)?.as_str(),
+            pid: caps.get(2)?.as_str(),
+            tid: "-",
+            tag: caps.get(4)?.as_str().trim(),
+            message: caps.get(3)?.as_str().trim(),
+            uid: None,
+            custom_format: None,
+        });
+    }
+
+    // 형식 8: Level/Tag: Message
+    if let Some(caps) = formats.has(LogFormats::TAG).then(|| TAG_ONLY_PATTERN.captures(line)).flatten() {
+        let (tag, message) = split_tag_message(caps.get(2)?.as_str());
+        if !tag.is_empty() {
+            return Some(LogFields {
+                line,
+                timestamp: "-",
+                level: caps.get(1)?.as_str(),
+                pid: "-",
+                tid: "-",
+                tag,
+                message,
+                uid: None,
+                custom_format: None,
+            });
+        }
+    }
+
+    None
+}
+
+/// 형식 판별 확실성 (confidence 옵션) - 옵션이 켜졌을 때만 나머지 형식까지 검사
+/// - 타임스탬프 뒷부분에 맞는 내장 형식 수가 n개면 1/n (하나만 맞으면 1.0)
+/// - 타임스탬프가 줄 맨 앞이 아니라 중간에서 발견됐으면(앞에 다른 내용이 붙은 줄) 절반
+fn format_confidence(fields: &LogFields<'_>) -> f64 {
+    if fields.custom_format.is_some() || fields.timestamp == "-" || EPOCH_PATTERN.is_match(fields.line) {
+        return 1.0;
+    }
+    let Some(time_match) = TIME_PATTERN.find(fields.line) else {
+        return 0.0;
+    };
+    let remaining = fields.line[time_match.end()..].trim();
+    let matched = [&*THREADTIME_SIMPLE, &*THREADTIME_COMPLEX, &*LEVEL_TAG_PATTERN, &*UID_THREADTIME_PATTERN]
+        .iter()
+        .filter(|pattern| pattern.is_match(remaining))
+        .count()
+        .max(1);
+
+    let mut confidence = 1.0 / matched as f64;
+    if time_match.start() > 0 {
+        confidence *= 0.5;
+    }
+    confidence
+}
+
+/// 내장 형식 집합 (formats 옵션) - 비트 하나가 형식 하나
+#[derive(Clone, Copy)]
+struct LogFormats(u8);
+
+impl LogFormats {
+    const SIMPLE: u8 = 1 << 0;
+    const THREADTIME: u8 = 1 << 1;
+    const LEVEL_TAG: u8 = 1 << 2;
+    const UID: u8 = 1 << 3;
+    const EPOCH: u8 = 1 << 4;
+    const BRIEF: u8 = 1 << 5;
+    const PROCESS: u8 = 1 << 6;
+    const TAG: u8 = 1 << 7;
+    /// 타임스탬프가 있는 형식 (1~5)
+    const TIMESTAMPED: u8 = Self::SIMPLE | Self::THREADTIME | Self::LEVEL_TAG | Self::UID | Self::EPOCH;
+    const ALL: Self = Self(Self::TIMESTAMPED | Self::BRIEF | Self::PROCESS | Self::TAG);
+
+    /// formats 옵션에서 쓰는 형식 이름 (README 형식 1~8 순서)
+    const NAMES: [(&'static str, u8); 8] = [
+        ("threadtime_simple", Self::SIMPLE),
+        ("threadtime", Self::THREADTIME),
+        ("level_tag", Self::LEVEL_TAG),
+        ("uid", Self::UID),
+        ("epoch", Self::EPOCH),
+        ("brief", Self::BRIEF),
+        ("process", Self::PROCESS),
+        ("tag", Self::TAG),
+    ];
+
+    /// match_builtin_fields가 형식을 시도하는 순서 (여러 형식에 맞는 줄은 앞의 형식으로 파싱됨)
+    const MATCH_ORDER: [u8; 8] = [
+        Self::EPOCH,
+        Self::SIMPLE,
+        Self::THREADTIME,
+        Self::LEVEL_TAG,
+        Self::UID,
+        Self::BRIEF,
+        Self::PROCESS,
+        Self::TAG,
+    ];
+
+    fn has(self, format: u8) -> bool {
+        self.0 & format != 0
+    }
+
+    /// 실제 파싱에서 이 줄을 받아 가는 내장 형식의 이름 (없으면 None)
+    fn matching_name(line: &str) -> Option<&'static str> {
+        let bit = Self::MATCH_ORDER
+            .into_iter()
+            .find(|&bit| match_builtin_fields(line, Self(bit)).is_some())?;
+        Self::NAMES.iter().find(|(_, known)| *known == bit).map(|(name, _)| *name)
+    }
+
+    fn from_names(names: &[String]) -> PyResult<Self> {
+        let mut bits = 0;
+        for name in names {
+            let Some((_, bit)) = Self::NAMES.iter().find(|(known, _)| known == name) else {
+                let known: Vec<&str> = Self::NAMES.iter().map(|(known, _)| *known).collect();
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "unknown format '{}', expected one of {}",
+                    name,
+                    known.join(", ")
+                )));
+            };
+            bits |= bit;
+        }
+        if bits == 0 {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>("formats must not be empty"));
+        }
+        Ok(Self(bits))
+    }
+
+    /// format_hint 인자: "auto"(전부), "threadtime"(타임스탬프 형식 1~5), "brief", "process", "tag"
+    fn from_hint(hint: &str) -> PyResult<Self> {
+        match hint {
+            "auto" => Ok(Self::ALL),
+            "threadtime" => Ok(Self(Self::TIMESTAMPED)),
+            "brief" => Ok(Self(Self::BRIEF)),
+            "process" => Ok(Self(Self::PROCESS)),
+            "tag" => Ok(Self(Self::TAG)),
+            _ => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "format_hint must be 'auto', 'threadtime', 'brief', 'process' or 'tag', got '{}'",
+                hint
+            ))),
+        }
+    }
+}
+
+impl Default for LogFormats {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+/// 한 줄 파싱의 순수 Rust 단계 - 내장 형식 전부, 그다음 사용자 형식을 시도해 필드 추출 (Python 객체 생성·GIL 불필요)
+/// dict 변환은 fields_to_dict, 옵션/상태 처리는 parse_entry가 담당 (Rust 테스트·벤치마크는 이 함수를 직접 호출)
+fn parse_line_inner(line: &str) -> Option<LogFields<'_>> {
+    match_log_fields_in(line, LogFormats::ALL)
+}
+
+/// formats에 든 형식만 시도해 필드 추출, 내장 형식이 모두 실패하면 사용자 형식 시도
+/// 사용자 형식 매칭이 시간 한도를 넘긴 줄은 파싱 실패(None)로 취급 (스캔 함수용)
+fn match_log_fields_in(line: &str, formats: LogFormats) -> Option<LogFields<'_>> {
+    match_entry_fields(line, formats).ok().flatten()
+}
+
+/// match_log_fields_in과 같지만 사용자 형식 매칭 시간 초과를 구분 (파싱 함수용 - regex_timeout 항목으로 남김)
+fn match_entry_fields(line: &str, formats: LogFormats) -> Result<Option<LogFields<'_>>, RegexTimeout> {
+    let line = line.trim();
+    if line.is_empty() {
+        return Ok(None);
+    }
+    match match_builtin_fields(line, formats) {
+        Some(fields) => Ok(Some(fields)),
+        None => match_custom_fields(line),
+    }
+}
+
+fn match_builtin_fields(line: &str, formats: LogFormats) -> Option<LogFields<'_>> {
+    match_timestamped_fields(line, formats).or_else(|| match_untimed_fields(line, formats))
+}
+
+/// 형식 1, 2, 4, 5의 헤더 뒤 "Tag: Message"를 첫 `: `(콜론 + 공백, 또는 끝의 콜론)에서 나눔
+/// 태그에 든 콜론(`AudioFlinger::Thread`)이나 메시지의 URL·`key:value`·시각은 나누지 않음
+/// 그런 구분자가 없으면 첫 콜론(`Tag:msg`), 콜론도 없으면 태그 "", 나머지 전체가 message
+fn split_tag_message(rest: &str) -> (&str, &str) {
+    let bytes = rest.as_bytes();
+    let separator = bytes
+        .iter()
+        .enumerate()
+        .position(|(i, &b)| b == b':' && bytes.get(i + 1).is_none_or(|next| next.is_ascii_whitespace()))
+        .or_else(|| rest.find(':'));
+    match separator {
+        Some(i) => (rest[..i].trim(), rest[i + 1..].trim()),
+        None => ("", rest.trim()),
+    }
+}
+
+/// 형식 1~5 (타임스탬프가 있는 형식)
+fn match_timestamped_fields(line: &str, formats: LogFormats) -> Option<LogFields<'_>> {
+    if !formats.has(LogFormats::TIMESTAMPED) {
+        return None;
+    }
+
+    // 형식 5: 1700000000.123  PID  TID  Level  Tag: Message (-v epoch, 줄 맨 앞이 큰 실수)
+    if let Some(caps) = formats.has(LogFormats::EPOCH).then(|| EPOCH_PATTERN.captures(line)).flatten() {
+        let (tag, message) = split_tag_message(caps.get(5).map_or("", |m| m.as_str()));
+        return Some(LogFields {
+            line,
+            timestamp: caps.get(1)?.as_str(),
+            level: caps.get(4)?.as_str(),
+            pid: caps.get(2)?.as_str(),
+            tid: caps.get(3)?.as_str(),
+            tag,
+            message,
+            uid: None,
+            custom_format: None,
+        });
+    }
+
+    // 시간 패턴 찾기
+    let time_match = TIME_PATTERN.find(line)?;
+    let timestamp = time_match.as_str();
+    let remaining = line[time_match.end()..].trim();
+
+    // 형식 1: mm-dd HH:MM:SS.mmm  PID  -  -  Tag: Message (Level 없음)
+    if let Some(caps) = formats.has(LogFormats::SIMPLE).then(|| THREADTIME_SIMPLE.captures(remaining)).flatten() {
+        let (tag, message) = split_tag_message(caps.get(2).map_or("", |m| m.as_str()));
+        return Some(LogFields {
+            line,
+            timestamp,
+            level: "-",
+            pid: caps.get(1)?.as_str(),
+            tid: "-",
+            tag,
+            message,
+            uid: None,
+            custom_format: None,
+        });
+    }
+
+    // 형식 2: mm-dd HH:MM:SS.mmm  Level  -  -  PID  TID  Level  Tag: Message
+    if let Some(caps) = formats.has(LogFormats::THREADTIME).then(|| THREADTIME_COMPLEX.captures(remaining)).flatten() {
+        let (tag, message) = split_tag_message(caps.get(5).map_or("", |m| m.as_str()));
+        return Some(LogFields {
+            line,
+            timestamp,
+            level: caps.get(4)?.as_str(),
+            pid: caps.get(2)?.as_str(),
+            tid: caps.get(3)?.as_str(),
+            tag,
+            message,
+            uid: None,
+            custom_format: None,
+        });
+    }
+
+    // 형식 3: Level/Tag(  PID  TID  Message
+    if let Some(caps) = formats.has(LogFormats::LEVEL_TAG).then(|| LEVEL_TAG_PATTERN.captures(remaining)).flatten() {
+        let pid_tid = caps.get(3)?.as_str().trim();
+        let mut pid_tid_parts = pid_tid.split_whitespace();
+        return Some(LogFields {
+            line,
+            timestamp,
+            level: caps.get(1)?.as_str(),
+            pid: pid_tid_parts.next().unwrap_or("-"),
+            tid: pid_tid_parts.next().unwrap_or("-"),
+            tag: caps.get(2)?.as_str().trim(),
+            message: caps.get(4)?.as_str().trim(),
+            uid: None,
+            custom_format: None,
+        });
+    }
+
+    // 형식 4: mm-dd HH:MM:SS.mmm  UID  PID  TID  Level  Tag: Message (-v uid)
+    if let Some(caps) = formats.has(LogFormats::UID).then(|| UID_THREADTIME_PATTERN.captures(remaining)).flatten() {
+        let (tag, message) = split_tag_message(caps.get(5).map_or("", |m| m.as_str()));
+        return Some(LogFields {
+            line,
+            timestamp,
+            level: caps.get(4)?.as_str(),
+            pid: caps.get(2)?.as_str(),
+            tid: caps.get(3)?.as_str(),
+            tag,
+            message,
+            uid: Some(caps.get(1)?.as_str()),
+            custom_format: None,
+        });
+    }
+
+    None
+}
+
+/// 파싱 옵션 - 파싱 함수들의 키워드 인자(**options)로 전달
+#[derive(Clone, Default)]
+struct ParseOptions {
+    /// init 서비스 시작/종료 로그를 INIT 이벤트로 표시
+    detect_init: bool,
+    /// 디스플레이 근거가 없는 줄에 같은 PID의 마지막 디스플레이를 이어서 적용
+    display_sticky: bool,
+    /// 결과 전체를 타임스탬프 순으로 안정 정렬 (parse_log_batch, parse_log_file_chunk)
+    sort_output: bool,
+    /// 휘발성 필드를 뺀 비교용 키 "{level}:{tag}:{숫자 마스킹된 message}" 추가
+    stable_key: bool,
+    /// binder 트랜잭션 실패 로그를 BINDER_FAIL 이벤트로 표시
+    detect_binder: bool,
+    /// 형식 판별의 확실성 confidence(0.0~1.0) 추가
+    confidence: bool,
+    /// tag/message 끝의 제어 문자(\0, \x04 등)를 그대로 둠
+    /// Python에는 strip_control(기본 True)로 노출되며 이 값은 그 반대
+    keep_control: bool,
+    /// 필드 값의 타당성 검사 결과 valid / validation_errors 추가
+    validate: bool,
+    /// 메시지 안에 다른 logcat 줄이 통째로 들어 있으면 다시 파싱해 nested로 추가
+    unwrap_nested: bool,
+    /// 연속 줄(스택 트레이스 등)을 앞 항목의 message에 합침. None이면 합치지 않음
+    merge_scope: Option<MergeScope>,
+    /// message 바이트의 Shannon 엔트로피 entropy(bit/byte) 추가
+    compute_entropy: bool,
+    /// ART 클래스 검증 / JIT 컴파일 로그를 JIT 이벤트로 표시
+    detect_jit: bool,
+    /// 크래시/ANR/톰스톤/워치독/LMK 지점에 event_type 추가
+    detect_events: bool,
+    /// 레벨 전체 이름 level_name (ERROR, WARN 등) 추가
+    level_name: bool,
+    /// `{`/`[`로 시작해 괄호가 닫히지 않은 메시지에 괄호가 맞을 때까지 다음 줄을 합침
+    merge_json: bool,
+    /// JSON 메시지를 파싱해 json 필드로 추가
+    parse_json: bool,
+    /// 연도 1월 1일 기준 나노초 ts_ns 추가 (-v nsec 정밀도 유지)
+    ts_ns: bool,
+    /// 밀리초 ts_ms 추가 (-v epoch는 1970년 기준 절대값)
+    ts_ms: bool,
+    /// 줄 필터 (dict 생성 전에 적용)
+    filters: LineFilters,
+    /// 필터를 통과한 항목에 만족한 필터 이름 목록 matched_filters 추가
+    explain_filter: bool,
+    /// 어떤 형식에도 맞지 않는 줄을 버리지 않고 {"raw": 원본, "parsed": False}로 반환
+    keep_unparsed: bool,
+    /// 메시지의 displayId → 디스플레이 이름 (기본 0/1/2 매핑보다 우선)
+    display_map: Option<Arc<HashMap<String, String>>>,
+    /// 호출별 PID → 프로세스 이름 (있으면 전역 PID_MAP 대신 사용)
+    pid_map: Option<Arc<HashMap<String, String>>>,
+    /// 디스플레이 분류 근거 display_source (+ displayId로 분류됐으면 display_id) 추가
+    display_source: bool,
+    /// 전체 결과를 심각한 레벨 순(A, F, E, W, I, D, V)으로 안정 정렬 (레벨 없는 항목은 맨 뒤)
+    sort_by_severity: bool,
+    /// 시도할 내장 형식 (기본 전부)
+    formats: LogFormats,
+    /// 타임스탬프 성분 month/day/hour/minute/second/millis(int) 추가
+    time_parts: bool,
+    /// BOM이 없는 파일을 읽을 인코딩 (파일 함수만 해당)
+    encoding: Option<&'static Encoding>,
+    /// 이보다 긴 줄(바이트, 개행 제외)은 읽기 오류로 건너뜀 (파일 함수만 해당, 앞부분만 메모리에 읽음)
+    max_line_length: Option<usize>,
+    /// 이 연도로 가정한 1970년 기준 밀리초 epoch_millis 추가
+    base_year: Option<i32>,
+    /// 타임스탬프로 시작하지 않는 줄을 바로 앞 항목의 message에 합침
+    aggregate_multiline: bool,
+    /// 연속된 같은 level/tag/message 항목을 첫 항목 하나로 합치고 count 추가
+    collapse_duplicates: bool,
+    /// 항목을 dict 대신 ParsedLog 객체로 반환
+    as_objects: bool,
+    /// 스트리밍 콜백에 행 dict 리스트 대신 {키: 열 리스트} dict 하나를 넘김
+    columnar: bool,
+    /// 필터를 통과한 항목 N개 중 1개만 남김 (첫 항목부터)
+    sample_rate: Option<u32>,
+    /// 항목이 이보다 많으면 전체에 고르게 퍼지도록 이 개수만 남김 (전체 결과를 반환하는 함수만)
+    max_rows: Option<usize>,
+    /// LogParser.add_transform으로 등록한 이 파서만의 변환 (전역 변환 다음에 적용)
+    transforms: Arc<Vec<Transform>>,
+}
+
+/// filter_* 옵션 - 설정된 필터를 모두 만족하는 줄만 남김
+#[derive(Clone, Default)]
+struct LineFilters {
+    /// 최소 레벨 (level_num 값, V<D<I<W<E<F<A). 레벨이 없는 "-"는 항상 통과
+    min_level: Option<u8>,
+    /// 허용 레벨 문자 (예: "WEF")
+    levels: Option<String>,
+    /// 허용 태그 (정확히 일치)
+    tags: Option<HashSet<String>>,
+    /// 허용 PID
+    pids: Option<HashSet<String>>,
+    /// message에 포함돼야 하는 문자열 (대소문자 구분)
+    message: Option<String>,
+    /// 허용 태그 패턴 (대소문자 무시, 끝의 `*`는 접두사 일치)
+    tag_include: Option<Vec<TagPattern>>,
+    /// 제외 태그 패턴 (tag_include와 겹치면 제외가 우선)
+    tag_exclude: Option<Vec<TagPattern>>,
+    /// 시작 시각 (MM-DD 형식의 연도 1월 1일 기준 나노초, 포함)
+    start_time: Option<i64>,
+    /// 끝 시각 (포함). start_time보다 작으면 연말을 넘어가는 범위
+    end_time: Option<i64>,
+}
+
+/// tag_include / tag_exclude 패턴 하나 (소문자로 저장)
+#[derive(Clone)]
+struct TagPattern {
+    text: String,
+    /// `Activity*`처럼 끝이 `*`이면 접두사 일치
+    prefix: bool,
+}
+
+impl TagPattern {
+    fn new(pattern: &str) -> Self {
+        let pattern = pattern.to_lowercase();
+        match pattern.strip_suffix('*') {
+            Some(prefix) => Self {
+                text: prefix.to_string(),
+                prefix: true,
+            },
+            None => Self {
+                text: pattern,
+                prefix: false,
+            },
+        }
+    }
+
+    /// tag_lower는 소문자로 바꾼 태그
+    fn matches(&self, tag_lower: &str) -> bool {
+        if self.prefix {
+            tag_lower.starts_with(&self.text)
+        } else {
+            tag_lower == self.text
+        }
+    }
+}
+
+impl LineFilters {
+    /// 모든 필터를 통과하면 만족한 (설정된) 필터 이름 목록, 하나라도 실패하면 None
+    fn matched(&self, fields: &LogFields<'_>) -> Option<Vec<&'static str>> {
+        let mut matched = Vec::new();
+        if let Some(min_level) = self.min_level {
+            if fields.level != "-" && level_num(fields.level) < min_level {
+                return None;
+            }
+            matched.push("min_level");
+        }
+        if let Some(levels) = &self.levels {
+            if !levels.contains(fields.level) {
+                return None;
+            }
+            matched.push("filter_level");
+        }
+        if let Some(tags) = &self.tags {
+            if !tags.contains(fields.tag) {
+                return None;
+            }
+            matched.push("filter_tag");
+        }
+        if let Some(pids) = &self.pids {
+            if !pids.contains(fields.pid) {
+                return None;
+            }
+            matched.push("filter_pid");
+        }
+        if let Some(message) = &self.message {
+            if !fields.message.contains(message.as_str()) {
+                return None;
+            }
+            matched.push("filter_message");
+        }
+        if self.start_time.is_some() || self.end_time.is_some() {
+            let nanos = month_day_nanos(fields.timestamp)?;
+            let after_start = self.start_time.is_none_or(|start| nanos >= start);
+            let before_end = self.end_time.is_none_or(|end| nanos <= end);
+            let in_range = match (self.start_time, self.end_time) {
+                // 12-31 23:00 ~ 01-01 01:00처럼 연말을 넘어가는 범위
+                (Some(start), Some(end)) if start > end => after_start || before_end,
+                _ => after_start && before_end,
+            };
+            if !in_range {
+                return None;
+            }
+            if self.start_time.is_some() {
+                matched.push("start_time");
+            }
+            if self.end_time.is_some() {
+                matched.push("end_time");
+            }
+        }
+        if self.tag_include.is_some() || self.tag_exclude.is_some() {
+            let tag_lower = fields.tag.to_lowercase();
+            if let Some(exclude) = &self.tag_exclude {
+                if exclude.iter().any(|pattern| pattern.matches(&tag_lower)) {
+                    return None;
+                }
+                matched.push("tag_exclude");
+            }
+            if let Some(include) = &self.tag_include {
+                if !include.iter().any(|pattern| pattern.matches(&tag_lower)) {
+                    return None;
+                }
+                matched.push("tag_include");
+            }
+        }
+        Some(matched)
+    }
+}
+
+/// `MM-DD HH:MM:SS.mmm` 타임스탬프만 연도 1월 1일 기준 나노초로 (-v epoch 등 다른 형식은 None)
+/// (월, 일, 시각) 순서와 같은 순서라 그대로 비교 가능
+fn month_day_nanos(timestamp: &str) -> Option<i64> {
+    if timestamp.as_bytes().get(2) != Some(&b'-') {
+        return None;
+    }
+    timestamp_to_nanos(timestamp)
+}
+
+/// start_time / end_time 인자 → 나노초. 소수부가 없으면 .000으로 봄
+fn parse_time_bound(name: &str, time: &str) -> PyResult<i64> {
+    let time = time.trim();
+    let normalized = if time.contains('.') { Cow::Borrowed(time) } else { Cow::Owned(format!("{}.000", time)) };
+    month_day_nanos(&normalized).ok_or_else(|| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "{} must be 'MM-DD HH:MM:SS.mmm', got '{}'",
+            name, time
+        ))
+    })
+}
+
+/// min_level 인자 ("W", 대소문자 무시) → level_num 값
+fn parse_min_level(level: &str) -> PyResult<u8> {
+    match level_num(&level.to_ascii_uppercase()) {
+        0 => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "min_level must be one of V, D, I, W, E, F, A, got '{}'",
+            level
+        ))),
+        num => Ok(num),
+    }
+}
+
+/// 문자열(또는 int) 하나 또는 그 리스트 → 문자열 집합
+fn extract_str_set(value: &Bound<'_, PyAny>) -> PyResult<HashSet<String>> {
+    if value.is_instance_of::<pyo3::types::PyString>() || value.is_instance_of::<pyo3::types::PyInt>() {
+        return Ok(HashSet::from([value.str()?.to_string()]));
+    }
+    value.iter()?.map(|item| Ok(item?.str()?.to_string())).collect()
+}
+
+/// tag_include / tag_exclude 인자 (문자열 하나 또는 리스트, None이나 빈 리스트는 필터 없음)
+fn extract_tag_patterns(value: &Bound<'_, PyAny>) -> PyResult<Option<Vec<TagPattern>>> {
+    if !value.is_truthy()? {
+        return Ok(None);
+    }
+    let patterns = extract_str_set(value)?;
+    Ok(Some(patterns.iter().map(|pattern| TagPattern::new(pattern)).collect()))
+}
+
+/// 연속 줄을 어느 항목에 합칠지 결정하는 범위
+#[derive(Clone, Copy, PartialEq)]
+enum MergeScope {
+    /// 바로 앞 항목 (스레드가 섞이면 다른 스레드 항목에 붙을 수 있음)
+    Global,
+    /// 같은 PID의 가장 최근 항목
+    Pid,
+    /// 같은 PID/TID의 가장 최근 항목 (TID가 없으면 PID 기준)
+    Tid,
+}
+
+impl MergeScope {
+    fn from_name(name: &str) -> PyResult<Self> {
+        match name {
+            "global" => Ok(Self::Global),
+            "pid" => Ok(Self::Pid),
+            "tid" => Ok(Self::Tid),
+            _ => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "merge_scope must be 'global', 'pid' or 'tid', got '{}'",
+                name
+            ))),
+        }
+    }
+
+    fn key(self, fields: &LogFields<'_>) -> String {
+        match self {
+            Self::Global => String::new(),
+            Self::Tid if fields.tid != "-" => format!("{}/{}", fields.pid, fields.tid),
+            _ => fields.pid.to_string(),
+        }
+    }
+}
+
+impl ParseOptions {
+    fn from_kwargs(kwargs: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+        let mut options = Self::default();
+        let Some(kwargs) = kwargs else {
+            return Ok(options);
+        };
+
+        for (key, value) in kwargs.iter() {
+            let key: String = key.extract()?;
+            match key.as_str() {
+                "detect_init" => options.detect_init = value.extract()?,
+                "display_sticky" => options.display_sticky = value.extract()?,
+                "sort_output" => options.sort_output = value.extract()?,
+                "stable_key" => options.stable_key = value.extract()?,
+                "detect_binder" => options.detect_binder = value.extract()?,
+                "confidence" => options.confidence = value.extract()?,
+                "strip_control" => options.keep_control = !value.extract::<bool>()?,
+                "validate" => options.validate = value.extract()?,
+                "unwrap_nested" => options.unwrap_nested = value.extract()?,
+                "compute_entropy" => options.compute_entropy = value.extract()?,
+                "detect_jit" => options.detect_jit = value.extract()?,
+                "detect_events" => options.detect_events = value.extract()?,
+                "level_name" => options.level_name = value.extract()?,
+                "merge_json" => options.merge_json = value.extract()?,
+                "parse_json" => options.parse_json = value.extract()?,
+                "ts_ns" => options.ts_ns = value.extract()?,
+                "ts_ms" => options.ts_ms = value.extract()?,
+                "time_parts" => options.time_parts = value.extract()?,
+                "encoding" => {
+                    options.encoding = value
+                        .extract::<Option<String>>()?
+                        .map(|label| parse_encoding(&label))
+                        .transpose()?
+                }
+                "base_year" => options.base_year = value.extract()?,
+                "max_line_length" => {
+                    options.max_line_length = value.extract()?;
+                    if options.max_line_length == Some(0) {
+                        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                            "max_line_length must be at least 1",
+                        ));
+                    }
+                }
+                "min_level" => {
+                    options.filters.min_level = value
+                        .extract::<Option<String>>()?
+                        .map(|level| parse_min_level(&level))
+                        .transpose()?
+                }
+                "filter_level" => options.filters.levels = value.extract()?,
+                "filter_tag" => options.filters.tags = value.is_truthy()?.then(|| extract_str_set(&value)).transpose()?,
+                "filter_pid" => options.filters.pids = value.is_truthy()?.then(|| extract_str_set(&value)).transpose()?,
+                "filter_message" => options.filters.message = value.extract()?,
+                "start_time" | "end_time" => {
+                    let bound = value
+                        .extract::<Option<String>>()?
+                        .map(|time| parse_time_bound(&key, &time))
+                        .transpose()?;
+                    if key == "start_time" {
+                        options.filters.start_time = bound;
+                    } else {
+                        options.filters.end_time = bound;
+                    }
+                }
+                "tag_include" => options.filters.tag_include = extract_tag_patterns(&value)?,
+                "tag_exclude" => options.filters.tag_exclude = extract_tag_patterns(&value)?,
+                "explain_filter" => options.explain_filter = value.extract()?,
+                "keep_unparsed" => options.keep_unparsed = value.extract()?,
+                "aggregate_multiline" => options.aggregate_multiline = value.extract()?,
+                "collapse_duplicates" => options.collapse_duplicates = value.extract()?,
+                "as_objects" => options.as_objects = value.extract()?,
+                "columnar" => options.columnar = value.extract()?,
+                "sample_rate" => {
+                    options.sample_rate = value.extract()?;
+                    if options.sample_rate == Some(0) {
+                        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>("sample_rate must be at least 1"));
+                    }
+                }
+                "max_rows" => {
+                    options.max_rows = value.extract()?;
+                    if options.max_rows == Some(0) {
+                        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>("max_rows must be at least 1"));
+                    }
+                }
+                "formats" => {
+                    options.formats = match value.extract::<Option<Vec<String>>>()? {
+                        Some(names) => LogFormats::from_names(&names)?,
+                        None => LogFormats::ALL,
+                    }
+                }
+                "format_hint" => {
+                    options.formats = match value.extract::<Option<String>>()? {
+                        Some(hint) => LogFormats::from_hint(&hint)?,
+                        None => LogFormats::ALL,
+                    }
+                }
+                "display_map" => options.display_map = value.extract::<Option<HashMap<String, String>>>()?.map(Arc::new),
+                "display_source" => options.display_source = value.extract()?,
+                "sort_by_severity" => options.sort_by_severity = value.extract()?,
+                "pid_map" => options.pid_map = value.extract::<Option<HashMap<String, String>>>()?.map(Arc::new),
+                "merge_scope" => {
+                    options.merge_scope = value
+                        .extract::<Option<String>>()?
+                        .map(|name| MergeScope::from_name(&name))
+                        .transpose()?
+                }
+                _ => {
+                    return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+                        "unexpected keyword argument '{}'",
+                        key
+                    )))
+                }
+            }
+        }
+        if options.columnar && options.as_objects {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "columnar and as_objects cannot be combined",
+            ));
+        }
+        Ok(options)
+    }
+
+    /// 연속 줄·중복을 합치는 옵션 - 청크의 마지막 항목이 다음 청크에서 갱신될 수 있음
+    fn holds_back_last(&self) -> bool {
+        self.aggregate_multiline || self.merge_scope.is_some() || self.collapse_duplicates
+    }
+
+    /// 청크 단위로 내보내는 함수(스트리밍·follow·parse_file_queue·parse_file_search)용 검사
+    /// max_rows·sort_output·sort_by_severity는 전체 결과가 필요하므로 거부 + check_chunked_output
+    fn check_streaming(&self) -> PyResult<()> {
+        if self.max_rows.is_some() {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "max_rows needs the whole result and is not supported by streaming functions, use sample_rate",
+            ));
+        }
+        if self.sort_output || self.sort_by_severity {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "sort_output and sort_by_severity need the whole result and are not supported by streaming functions",
+            ));
+        }
+        self.check_chunked_output()
+    }
+
+    /// 청크마다 항목을 내보내는 함수(스트리밍, parse_file_queue, parse_file_search)용 검사
+    /// merge_scope=pid/tid와 merge_json은 이미 내보낸 다른 PID의 항목에 나중 줄을 이어 붙이는데,
+    /// columnar·as_objects는 내보낼 때 dict를 변환하므로 그 줄이 사라짐 → 함께 쓰면 ValueError
+    fn check_chunked_output(&self) -> PyResult<()> {
+        let merges_per_pid = matches!(self.merge_scope, Some(MergeScope::Pid | MergeScope::Tid)) || self.merge_json;
+        if merges_per_pid && (self.columnar || self.as_objects) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "merge_scope='pid'/'tid' and merge_json cannot be combined with columnar or as_objects in chunked functions",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// 여러 줄에 걸쳐 유지되는 파싱 상태 (한 파일/배치 단위)
+#[derive(Default)]
+struct ParseState {
+    /// display_sticky: PID별 마지막으로 분류된 디스플레이
+    sticky_displays: HashMap<String, Cow<'static, str>>,
+    /// merge_scope: 범위 키별 연속 줄을 받을 가장 최근 항목
+    merge_targets: HashMap<String, Py<PyDict>>,
+    /// merge_scope: 가장 최근 항목 (PID가 없는 파싱 실패 줄을 받음)
+    last_entry: Option<Py<PyDict>>,
+    /// merge_json: PID별 괄호가 아직 닫히지 않은 JSON 메시지 항목
+    pending_json: HashMap<String, PendingJson>,
+    /// merge_json: 가장 최근에 JSON 모으기를 시작한 PID (파싱되지 않는 줄을 받음)
+    last_json_pid: Option<String>,
+    /// collapse_duplicates: 직전에 반환한 항목과 같은 내용이 이어진 횟수
+    duplicate_run: Option<DuplicateRun>,
+    /// 항목 dict를 만들거나 변환(add_transform)을 적용하다 난 첫 오류 - 호출자가 take_error로 전파
+    error: Option<PyErr>,
+}
+
+/// collapse_duplicates: 연속된 같은 (level, tag, message) 항목 중 첫 항목
+struct DuplicateRun {
+    level: String,
+    tag: String,
+    message: String,
+    entry: Py<PyDict>,
+    count: usize,
+}
+
+/// merge_json: 여러 줄에 걸친 JSON 메시지를 모으는 중인 항목
+struct PendingJson {
+    entry: Py<PyDict>,
+    depth: JsonDepth,
+    lines: usize,
+}
+
+// 괄호가 끝내 닫히지 않는 메시지가 파일 전체를 삼키지 않도록 하는 상한 (줄 수)
+const MAX_JSON_LINES: usize = 1000;
+
+/// JSON 괄호 깊이 - 문자열 안의 괄호와 이스케이프된 따옴표는 세지 않음
+#[derive(Default)]
+struct JsonDepth {
+    depth: i32,
+    in_string: bool,
+    escaped: bool,
+}
+
+impl JsonDepth {
+    fn feed(&mut self, text: &str) {
+        for b in text.bytes() {
+            if self.in_string {
+                match b {
+                    _ if self.escaped => self.escaped = false,
+                    b'\\' => self.escaped = true,
+                    b'"' => self.in_string = false,
+                    _ => {}
+                }
+                continue;
+            }
+            match b {
+                b'"' => self.in_string = true,
+                b'{' | b'[' => self.depth += 1,
+                b'}' | b']' => self.depth -= 1,
+                _ => {}
+            }
+        }
+    }
+
+    fn is_balanced(&self) -> bool {
+        self.depth <= 0
+    }
+}
+
+/// 항목의 message 뒤에 "\n" + text를 이어 붙임 (message가 문자열이 아니면 false)
+fn append_message(target: &Bound<'_, PyDict>, text: &str) -> bool {
+    let Some(message) = target
+        .get_item("message")
+        .ok()
+        .flatten()
+        .and_then(|message| message.extract::<String>().ok())
+    else {
+        return false;
+    };
+    target.set_item("message", format!("{}\n{}", message, text)).is_ok()
+}
+
+/// parse_json: 완성된 JSON 메시지를 Python json 모듈로 파싱해 json 필드로 추가 (실패하면 무시)
+fn attach_json(py: Python<'_>, target: &Bound<'_, PyDict>) {
+    let parsed = target
+        .get_item("message")
+        .ok()
+        .flatten()
+        .and_then(|message| py.import_bound("json").ok()?.call_method1("loads", (message,)).ok());
+    if let Some(parsed) = parsed {
+        let _ = target.set_item("json", parsed);
+    }
+}
+
+impl ParseState {
+    /// build_entry에서 난 오류가 있으면 꺼내 반환 (그 줄은 항목으로 만들어지지 않음)
+    fn take_error(&mut self) -> PyResult<()> {
+        self.error.take().map_or(Ok(()), Err)
+    }
+    /// 디스플레이 분류. display_sticky면 근거 없는 줄(기본값 Main)에
+    /// 같은 PID에서 마지막으로 분류된 디스플레이를 적용
+    fn resolve_display<'a>(&mut self, fields: &LogFields<'a>, options: &ParseOptions) -> DisplayMatch<'a> {
+        let matched = match_display_detail(fields.tag, fields.pid, fields.message, options.display_map.as_deref());
+        if !options.display_sticky || fields.pid == "-" {
+            return matched.unwrap_or_default();
+        }
+
+        match matched {
+            Some(matched) => {
+                self.sticky_displays.insert(fields.pid.to_string(), matched.display.clone());
+                matched
+            }
+            None => match self.sticky_displays.get(fields.pid) {
+                Some(display) => DisplayMatch::new(display.clone(), "sticky"),
+                None => DisplayMatch::default(),
+            },
+        }
+    }
+
+    /// 연속 줄이면 범위에 맞는 앞 항목의 message 뒤에 "\n"으로 이어 붙이고 true
+    /// - 파싱되지 않는 줄은 PID를 알 수 없으므로 범위와 관계없이 가장 최근 항목에 붙임
+    /// - 파싱된 줄은 message가 연속 줄 패턴일 때만, 같은 범위의 항목이 없으면 새 항목으로 남김
+    fn merge_continuation(
+        &mut self,
+        py: Python<'_>,
+        line: &str,
+        fields: Option<&LogFields<'_>>,
+        scope: MergeScope,
+        options: &ParseOptions,
+    ) -> bool {
+        let (target, text) = match fields {
+            Some(fields) if CONTINUATION_PATTERN.is_match(fields.message) => {
+                (self.merge_targets.get(&scope.key(fields)), fields.message)
+            }
+            Some(_) => return false,
+            None if !line.trim().is_empty() => (self.last_entry.as_ref(), line.trim()),
+            None => return false,
+        };
+        let Some(target) = target else {
+            return false;
+        };
+
+        let text = if options.keep_control { text } else { strip_trailing_control(text) };
+        append_message(target.bind(py), text)
+    }
+
+    /// 새 항목의 message가 `{`/`[`로 시작하는데 괄호가 닫히지 않았으면 이어지는 줄을 모으기 시작
+    fn begin_json(&mut self, py: Python<'_>, fields: &LogFields<'_>, entry: &PyObject, options: &ParseOptions) {
+        let message = fields.message.trim_start();
+        if !message.starts_with(['{', '[']) {
+            return;
+        }
+        let Ok(dict) = entry.bind(py).downcast::<PyDict>() else {
+            return;
+        };
+
+        let mut depth = JsonDepth::default();
+        depth.feed(message);
+        if depth.is_balanced() {
+            if options.parse_json {
+                attach_json(py, dict);
+            }
+            return;
+        }
+        self.pending_json.insert(
+            fields.pid.to_string(),
+            PendingJson {
+                entry: dict.clone().unbind(),
+                depth,
+                lines: 1,
+            },
+        );
+        self.last_json_pid = Some(fields.pid.to_string());
+    }
+
+    /// 같은 PID에서 JSON을 모으는 중이면 이 줄을 이어 붙이고 true
+    /// - 헤더가 있는 줄은 message를 붙임 (다른 PID의 줄이 끼어들어도 섞이지 않음)
+    /// - 파싱되지 않는 줄은 PID를 알 수 없어 가장 최근에 시작한 JSON에 줄 전체를 붙임
+    fn continue_json(&mut self, py: Python<'_>, line: &str, fields: Option<&LogFields<'_>>, options: &ParseOptions) -> bool {
+        let (pid, text) = match fields {
+            Some(fields) => (fields.pid, fields.message),
+            None => match self.last_json_pid.as_deref() {
+                Some(pid) => (pid, line.trim()),
+                None => return false,
+            },
+        };
+        let Some(pending) = self.pending_json.get_mut(pid) else {
+            return false;
+        };
+        let pid = pid.to_string();
+        let text = if options.keep_control { text } else { strip_trailing_control(text) };
+
+        let target = pending.entry.bind(py).clone();
+        if !append_message(&target, text) {
+            self.finish_json(&pid);
+            return false;
+        }
+        pending.depth.feed(text);
+        pending.lines += 1;
+
+        if pending.depth.is_balanced() {
+            if options.parse_json {
+                attach_json(py, &target);
+            }
+            self.finish_json(&pid);
+        } else if pending.lines >= MAX_JSON_LINES {
+            self.finish_json(&pid);
+        }
+        true
+    }
+
+    fn finish_json(&mut self, pid: &str) {
+        self.pending_json.remove(pid);
+        if self.last_json_pid.as_deref() == Some(pid) {
+            self.last_json_pid = None;
+        }
+    }
+
+    /// 새 항목을 연속 줄 대상으로 기억
+    fn remember_entry(&mut self, py: Python<'_>, fields: &LogFields<'_>, scope: MergeScope, entry: &PyObject) {
+        if let Ok(dict) = entry.bind(py).downcast::<PyDict>() {
+            self.merge_targets.insert(scope.key(fields), dict.clone().unbind());
+            self.last_entry = Some(dict.clone().unbind());
+        }
+    }
+
+    /// collapse_duplicates: 직전 항목과 level/tag/message가 같으면 직전 항목의 count를 올리고 true
+    /// 다르면 이 항목을 새 묶음의 첫 항목으로 기억 (count=1)
+    fn collapse_duplicate(&mut self, py: Python<'_>, fields: &LogFields<'_>, entry: &PyObject, options: &ParseOptions) -> bool {
+        let (tag, message) = if options.keep_control {
+            (fields.tag, fields.message)
+        } else {
+            (strip_trailing_control(fields.tag), strip_trailing_control(fields.message))
+        };
+        if let Some(run) = &mut self.duplicate_run {
+            if run.level == fields.level && run.tag == tag && run.message == message {
+                run.count += 1;
+                let _ = run.entry.bind(py).set_item("count", run.count);
+                return true;
+            }
+        }
+
+        self.duplicate_run = entry.bind(py).downcast::<PyDict>().ok().map(|dict| {
+            let _ = dict.set_item("count", 1);
+            DuplicateRun {
+                level: fields.level.to_string(),
+                tag: tag.to_string(),
+                message: message.to_string(),
+                entry: dict.clone().unbind(),
+                count: 1,
+            }
+        });
+        false
+    }
+
+    /// aggregate_multiline: 타임스탬프로 시작하지 않는 줄이면 가장 최근 항목의 message에 붙이고 true
+    fn aggregate_line(&mut self, py: Python<'_>, line: &str, options: &ParseOptions) -> bool {
+        let line = line.trim();
+        if line.is_empty() || starts_with_timestamp(line) {
+            return false;
+        }
+        let Some(target) = &self.last_entry else {
+            return false;
+        };
+        let text = if options.keep_control { line } else { strip_trailing_control(line) };
+        append_message(target.bind(py), text)
+    }
+}
+
+/// 줄 맨 앞이 타임스탬프(MM-DD HH:MM:SS.mmm 또는 -v epoch 초)인지
+fn starts_with_timestamp(line: &str) -> bool {
+    TIME_PATTERN.find(line).is_some_and(|time| time.start() == 0) || EPOCH_PATTERN.is_match(line)
+}
+
+/// init 서비스 상태 변화
+struct InitEvent<'a> {
+    service: &'a str,
+    /// "starting" | "exited" | "signaled"
+    state: &'static str,
+    service_pid: Option<&'a str>,
+    exit_status: Option<i32>,
+    signal: Option<i32>,
+}
+
+/// init 태그 메시지에서 서비스 시작/종료 추출
+/// - `starting service 'vold'...`
+/// - `Service 'bootanim' (pid 612) exited with status 0`
+/// - `Service 'adbd' (pid 433) received signal 9` / `killed by signal 9`
+fn detect_init_event<'a>(tag: &str, message: &'a str) -> Option<InitEvent<'a>> {
+    if tag != "init" {
+        return None;
+    }
+
+    if let Some(caps) = INIT_START_PATTERN.captures(message) {
+        return Some(InitEvent {
+            service: caps.get(1)?.as_str(),
+            state: "starting",
+            service_pid: None,
+            exit_status: None,
+            signal: None,
+        });
+    }
+
+    if let Some(caps) = INIT_EXIT_PATTERN.captures(message) {
+        let code = caps.get(4)?.as_str().parse().ok();
+        let exited = caps.get(3)?.as_str() == "exited with status";
+        return Some(InitEvent {
+            service: caps.get(1)?.as_str(),
+            state: if exited { "exited" } else { "signaled" },
+            service_pid: Some(caps.get(2)?.as_str()),
+            exit_status: if exited { code } else { None },
+            signal: if exited { None } else { code },
+        });
+    }
+
+    None
+}
+
+/// detect_jit: ART 클래스 검증 / JIT 컴파일 로그에서 뽑은 정보
+struct JitEvent<'a> {
+    /// "verification", "jit_compile", "lock_verification"
+    kind: &'static str,
+    /// 메서드 시그니처 또는 클래스 이름
+    target: &'a str,
+    /// 소요 시간 (로그에 있을 때만, ms로 환산)
+    duration_ms: Option<f64>,
+}
+
+/// 시간 값 + 단위(ns/us/ms/s) → ms
+fn duration_to_ms(value: &str, unit: &str) -> Option<f64> {
+    let value: f64 = value.parse().ok()?;
+    let scale = match unit {
+        "ns" => 1e-6,
+        "us" => 1e-3,
+        "ms" => 1.0,
+        "s" => 1000.0,
+        _ => return None,
+    };
+    Some(value * scale)
+}
+
+/// 앱 콜드 스타트 분석용 ART(art/zygote/dalvikvm 태그) 검증·JIT 로그 감지
+/// 태그는 기기마다 달라서 메시지만 봄
+fn detect_jit_event(message: &str) -> Option<JitEvent<'_>> {
+    if let Some(caps) = JIT_VERIFY_PATTERN.captures(message) {
+        return Some(JitEvent {
+            kind: "verification",
+            target: caps.get(1)?.as_str(),
+            duration_ms: duration_to_ms(caps.get(2)?.as_str(), caps.get(3)?.as_str()),
+        });
+    }
+    if let Some(caps) = JIT_COMPILED_PATTERN.captures(message) {
+        let duration_ms = match (caps.get(2), caps.get(3)) {
+            (Some(value), Some(unit)) => duration_to_ms(value.as_str(), unit.as_str()),
+            _ => None,
+        };
+        return Some(JitEvent {
+            kind: "jit_compile",
+            target: caps.get(1)?.as_str(),
+            duration_ms,
+        });
+    }
+    let caps = JIT_LOCK_VERIFY_PATTERN.captures(message)?;
+    Some(JitEvent {
+        kind: "lock_verification",
+        target: caps.get(1)?.as_str(),
+        duration_ms: None,
+    })
+}
+
+/// binder 트랜잭션 실패 여부와 (있으면) 에러 코드
+/// - `IPCThreadState: Binder transaction failure. id: 12, BR_*: 29201, error: -28 (No space left on device)`
+/// - `binder: 1234:1250 transaction failed 29189/-22, size 96-0 line 3008`
+/// - `JavaBinder: !!! FAILED BINDER TRANSACTION !!!  (parcel size = 1048676)`
+/// - `failed to transact: -32`, `status: FAILED_TRANSACTION`
+fn detect_binder_failure(message: &str) -> Option<Option<i32>> {
+    if !BINDER_FAIL_PATTERN.is_match(message) {
+        return None;
+    }
+    let code = BINDER_ERROR_CODE_PATTERN
+        .captures(message)
+        .and_then(|caps| caps.get(1)?.as_str().parse().ok());
+    Some(code)
+}
+
+/// 장애 지점 표시 (detect_events, find_events) → event_type
+/// - `CRASH`: `FATAL EXCEPTION: main` (AndroidRuntime 크래시 헤더, `FATAL EXCEPTION IN SYSTEM PROCESS` 포함)
+/// - `ANR`: `ANR in com.foo (...)` (ActivityManager)
+/// - `TOMBSTONE`: `*** *** *** ...` 네이티브 크래시 덤프 시작, `Tombstone written to: ...`, tombstoned의 `received crash request`
+/// - `WATCHDOG`: `*** WATCHDOG KILLING SYSTEM PROCESS: ...`
+/// - `LMK`: lowmemorykiller 태그의 `Kill '...'` / `Killing '...'`
+///
+/// 정규식 없이 이미 뽑은 tag/message의 접두사·포함 검사만 하므로 가벼움
+fn detect_failure_event(tag: &str, message: &str) -> Option<&'static str> {
+    if message.contains("FATAL EXCEPTION") {
+        Some("CRASH")
+    } else if message.starts_with("ANR in ") {
+        Some("ANR")
+    } else if message.starts_with("*** *** ***")
+        || message.starts_with("Tombstone written to")
+        || (tag == "tombstoned" && message.starts_with("received crash request"))
+    {
+        Some("TOMBSTONE")
+    } else if message.contains("WATCHDOG KILLING SYSTEM PROCESS") {
+        Some("WATCHDOG")
+    } else if tag == "lowmemorykiller" && message.starts_with("Kill") {
+        Some("LMK")
+    } else {
+        None
+    }
+}
+
+/// 필드 값 타당성 검사 (validate 옵션) - 문제가 있는 항목의 코드 목록
+/// 정규식이 느슨하게 맞은 줄이나 상위 형식 변경으로 파서 결과가 틀어진 것을 잡기 위함
+fn validation_errors(fields: &LogFields<'_>) -> Vec<&'static str> {
+    let is_id = |value: &str| value == "-" || (!value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()));
+
+    let mut errors = Vec::new();
+    if !matches!(fields.level, "V" | "D" | "I" | "W" | "E" | "A" | "F" | "-") {
+        errors.push("invalid_level");
+    }
+    if !is_id(fields.pid) {
+        errors.push("invalid_pid");
+    }
+    if !is_id(fields.tid) {
+        errors.push("invalid_tid");
+    }
+    if fields.tag.is_empty() {
+        errors.push("empty_tag");
+    }
+    if timestamp_to_millis(fields.timestamp).is_none() {
+        errors.push("invalid_timestamp");
+    }
+    errors
+}
+
+/// 메시지 안의 중첩 logcat 줄 추출 (로깅 프록시가 다른 프로세스의 로그를 그대로 남긴 경우)
+/// 타임스탬프가 있는 내장 형식(1~5)을 먼저 시도하고, 없으면 brief 형식(`I/Tag( 123): msg`)을 찾음
+/// tag/process 형식과 사용자 형식은 `I/O error: ...` 같은 일반 메시지에도 맞으므로 시도하지 않음
+/// 한 단계만 풀고 더 안쪽은 다시 풀지 않음
+fn nested_fields(message: &str) -> Option<LogFields<'_>> {
+    if let Some(fields) = match_timestamped_fields(message.trim(), LogFormats::ALL) {
+        return Some(fields);
+    }
+
+    let caps = NESTED_BRIEF_PATTERN.captures(message)?;
+    Some(LogFields {
+        line: caps.get(0)?.as_str().trim(),
+        timestamp: "-",
+        level: caps.get(1)?.as_str(),
+        pid: caps.get(3)?.as_str(),
+        tid: "-",
+        tag: caps.get(2)?.as_str().trim(),
+        message: caps.get(4)?.as_str().trim(),
+        uid: None,
+        custom_format: None,
+    })
+}
+
+/// 메시지의 캐리지 리턴 정리 (Windows 도구를 거친 CRLF 캡처, 버퍼가 합쳐져 줄 중간에 남은 `\r`)
+/// 끝의 `\r`은 제거하고, 중간의 `\r\n`과 단독 `\r`은 `\n`으로 바꿈 (Python universal newline과 같음)
+fn normalize_carriage_returns(message: &str) -> Cow<'_, str> {
+    let message = message.trim_end_matches('\r');
+    if message.contains('\r') {
+        Cow::Owned(message.replace("\r\n", "\n").replace('\r', "\n"))
+    } else {
+        Cow::Borrowed(message)
+    }
+}
+
+/// 메시지의 숫자(PID, 시간, 주소, 카운터 등)를 '#'으로 바꿔 같은 종류의 메시지를 같은 문자열로 맞춤
+/// 예: "took 35ms (pid 1234, 0x7f3a)" → "took #ms (pid #, #)"
+fn normalize_message(message: &str) -> Cow<'_, str> {
+    NUMBER_PATTERN.replace_all(message, "#")
+}
+
+/// 바이트 단위 Shannon 엔트로피 (0.0 ~ 8.0 bit/byte, 빈 문자열은 0.0)
+/// 일반 텍스트는 대략 3.5~4.5, base64 덩어리는 5.5~6, hex 덤프는 문자 종류가 16개뿐이라 4 이하
+fn shannon_entropy(bytes: &[u8]) -> f64 {
+    if bytes.is_empty() {
+        return 0.0;
+    }
+    let mut counts = [0usize; 256];
+    for &b in bytes {
+        counts[b as usize] += 1;
+    }
+    let len = bytes.len() as f64;
+    counts
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// time_parts / base_year: 타임스탬프 성분과 epoch_millis 추가 (해석할 수 없으면 넣지 않음)
+/// -v epoch 형식은 이미 절대 시각이므로 UTC 기준 성분과 원래 값을 그대로 씀
+fn add_time_parts(dict: &Bound<'_, PyDict>, timestamp: &str, options: &ParseOptions) -> PyResult<()> {
+    let (parts, epoch_millis) = match epoch_to_millis(timestamp) {
+        Some(epoch_millis) => (TimeParts::from_epoch_millis(epoch_millis), Some(epoch_millis)),
+        None => {
+            let Some(parts) = TimeParts::parse(timestamp) else {
+                return Ok(());
+            };
+            let epoch_millis = options.base_year.map(|year| parts.epoch_millis(i64::from(year)));
+            (parts, epoch_millis)
+        }
+    };
+
+    if options.time_parts {
+        dict.set_item("month", parts.month)?;
+        dict.set_item("day", parts.day)?;
+        dict.set_item("hour", parts.hour)?;
+        dict.set_item("minute", parts.minute)?;
+        dict.set_item("second", parts.second)?;
+        dict.set_item("millis", parts.millis)?;
+    }
+    if options.base_year.is_some() {
+        if let Some(epoch_millis) = epoch_millis {
+            dict.set_item("epoch_millis", epoch_millis)?;
+        }
+    }
+    Ok(())
+}
+
+/// 추출한 필드로 결과 dict 생성
+fn fields_to_dict<'py>(
+    py: Python<'py>,
+    fields: &LogFields<'_>,
+    display: &str,
+    options: &ParseOptions,
+) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new_bound(py);
+    dict.set_item("timestamp", fields.timestamp)?;
+    dict.set_item("level", fields.level)?;
+    dict.set_item("pid", fields.pid)?;
+    dict.set_item("tid", fields.tid)?;
+    dict.set_item("tag", fields.tag)?;
+    dict.set_item("message", normalize_carriage_returns(fields.message))?;
+    dict.set_item("display", display)?;
+    if let Some(uid) = fields.uid {
+        dict.set_item("uid", uid)?;
+    }
+    if let Some(index) = fields.custom_format {
+        add_custom_fields(&dict, fields.line, index)?;
+    }
+    let process = match options.pid_map.as_deref() {
+        Some(pid_map) => pid_map.get(fields.pid).cloned(),
+        None => PID_MAP.read().unwrap().get(fields.pid).cloned(),
+    };
+    if let Some(process) = process {
+        dict.set_item("process", process)?;
+    }
+    if options.level_name {
+        dict.set_item("level_name", level_full_name(fields.level))?;
+    }
+    if options.ts_ms {
+        if let Some(ms) = timestamp_to_millis(fields.timestamp) {
+            dict.set_item("ts_ms", ms)?;
+        }
+    }
+    if options.time_parts || options.base_year.is_some() {
+        add_time_parts(&dict, fields.timestamp, options)?;
+    }
+    if options.ts_ns {
+        if let Some(ns) = timestamp_to_nanos(fields.timestamp) {
+            dict.set_item("ts_ns", ns)?;
+        }
+    }
+
+    if options.unwrap_nested {
+        if let Some(inner) = nested_fields(fields.message) {
+            let nested = PyDict::new_bound(py);
+            nested.set_item("timestamp", inner.timestamp)?;
+            nested.set_item("level", inner.level)?;
+            nested.set_item("pid", inner.pid)?;
+            nested.set_item("tid", inner.tid)?;
+            nested.set_item("tag", inner.tag)?;
+            nested.set_item("message", normalize_carriage_returns(inner.message))?;
+            if let Some(uid) = inner.uid {
+                nested.set_item("uid", uid)?;
+            }
+            dict.set_item("nested", nested)?;
+        }
+    }
+
+    if options.compute_entropy {
+        dict.set_item("entropy", shannon_entropy(fields.message.as_bytes()))?;
+    }
+
+    if options.validate {
+        let errors = validation_errors(fields);
+        dict.set_item("valid", errors.is_empty())?;
+        if !errors.is_empty() {
+            dict.set_item("validation_errors", errors)?;
+        }
+    }
+
+    if options.confidence {
+        dict.set_item("confidence", format_confidence(fields))?;
+    }
+
+    if options.stable_key {
+        let key = format!("{}:{}:{}", fields.level, fields.tag, normalize_message(fields.message));
+        dict.set_item("key", key)?;
+    }
+
+    // 이벤트 감지기는 먼저 맞은 하나만 event_type을 채움
+    let mut has_event = false;
+    if options.detect_init {
+        if let Some(event) = detect_init_event(fields.tag, fields.message) {
+            dict.set_item("event_type", "INIT")?;
+            dict.set_item("service", event.service)?;
+            dict.set_item("service_state", event.state)?;
+            if let Some(service_pid) = event.service_pid {
+                dict.set_item("service_pid", service_pid)?;
+            }
+            if let Some(exit_status) = event.exit_status {
+                dict.set_item("exit_status", exit_status)?;
+            }
+            if let Some(signal) = event.signal {
+                dict.set_item("signal", signal)?;
+            }
+            has_event = true;
+        }
+    }
+
+    if options.detect_binder && !has_event {
+        if let Some(code) = detect_binder_failure(fields.message) {
+            dict.set_item("event_type", "BINDER_FAIL")?;
+            if let Some(code) = code {
+                dict.set_item("binder_error", code)?;
+            }
+            has_event = true;
+        }
+    }
+
+    if options.detect_jit && !has_event {
+        if let Some(event) = detect_jit_event(fields.message) {
+            dict.set_item("event_type", "JIT")?;
+            dict.set_item("jit_kind", event.kind)?;
+            dict.set_item("jit_target", event.target)?;
+            if let Some(duration_ms) = event.duration_ms {
+                dict.set_item("jit_ms", duration_ms)?;
+            }
+            has_event = true;
+        }
+    }
+
+    if options.detect_events && !has_event {
+        if let Some(event_type) = detect_failure_event(fields.tag, fields.message) {
+            dict.set_item("event_type", event_type)?;
+        }
+    }
+    Ok(dict)
+}
+
+/// 한 줄 파싱 → dict (파싱 실패 시 None)
+/// merge_json / merge_scope가 있으면 앞 항목에 합쳐진 줄은 None
+fn parse_entry(py: Python<'_>, line: &str, options: &ParseOptions, state: &mut ParseState) -> Option<PyObject> {
+    parse_matched_entry(py, line, match_entry_fields(line, options.formats), options, state)
+}
+
+/// parse_entry에서 정규식 매칭을 미리 끝낸 경우 (병렬 매칭 결과를 순서대로 dict로 만들 때)
+fn parse_matched_entry(
+    py: Python<'_>,
+    line: &str,
+    fields: Result<Option<LogFields<'_>>, RegexTimeout>,
+    options: &ParseOptions,
+    state: &mut ParseState,
+) -> Option<PyObject> {
+    let Ok(fields) = fields else {
+        // 사용자 형식 매칭 시간 초과 - keep_unparsed와 무관하게 항상 남김
+        state.duplicate_run = None;
+        return regex_timeout_entry(py, line);
+    };
+    if options.merge_json && state.continue_json(py, line, fields.as_ref(), options) {
+        return None;
+    }
+    if let Some(scope) = options.merge_scope {
+        if state.merge_continuation(py, line, fields.as_ref(), scope, options) {
+            return None;
+        }
+    }
+    if options.aggregate_multiline && fields.is_none() && state.aggregate_line(py, line, options) {
+        return None;
+    }
+
+    let Some(fields) = fields else {
+        let entry = options.keep_unparsed.then(|| unparsed_entry(py, line)).flatten();
+        if entry.is_some() {
+            // 사이에 다른 항목이 끼었으므로 연속이 아님
+            state.duplicate_run = None;
+        }
+        return entry;
+    };
+    let entry = build_entry(py, &fields, options, state)?;
+    if options.collapse_duplicates && state.collapse_duplicate(py, &fields, &entry, options) {
+        return None;
+    }
+    if let Some(scope) = options.merge_scope {
+        state.remember_entry(py, &fields, scope, &entry);
+    } else if options.aggregate_multiline {
+        state.last_entry = entry.bind(py).downcast::<PyDict>().ok().map(|dict| dict.clone().unbind());
+    }
+    if options.merge_json || options.parse_json {
+        state.begin_json(py, &fields, &entry, options);
+    }
+    Some(entry)
+}
+
+/// keep_unparsed: 파싱하지 못한 줄 → {"raw": line, "parsed": False}
+fn unparsed_entry(py: Python<'_>, line: &str) -> Option<PyObject> {
+    let dict = PyDict::new_bound(py);
+    dict.set_item("raw", line).ok()?;
+    dict.set_item("parsed", false).ok()?;
+    Some(dict.into())
+}
+
+/// 사용자 형식 매칭이 시간 한도를 넘긴 줄 → {"raw": line, "parsed": False, "regex_timeout": True}
+fn regex_timeout_entry(py: Python<'_>, line: &str) -> Option<PyObject> {
+    let dict = PyDict::new_bound(py);
+    dict.set_item("raw", line.trim()).ok()?;
+    dict.set_item("parsed", false).ok()?;
+    dict.set_item("regex_timeout", true).ok()?;
+    Some(dict.into())
+}
+
+/// 끝에 붙은 제어 문자(0x20 미만, 탭 제외) 제거
+/// trim()은 공백 문자만 지우므로 일부 장치가 붙이는 \0, \x04 등이 메시지에 남음
+fn strip_trailing_control(value: &str) -> &str {
+    value.trim_end_matches(|c: char| (c as u32) < 0x20 && c != '\t')
+}
+
+/// 추출한 필드 → 필터 + 디스플레이 분류 + dict 생성 + 변환 적용 (필터에서 걸러지면 None)
+/// dict 생성·변환 중 오류가 나면 None을 반환하고 오류는 state.error에 남김 (호출자가 take_error로 전파)
+fn build_entry(py: Python<'_>, fields: &LogFields<'_>, options: &ParseOptions, state: &mut ParseState) -> Option<PyObject> {
+    let mut fields = *fields;
+    if !options.keep_control {
+        fields.tag = strip_trailing_control(fields.tag);
+        fields.message = strip_trailing_control(fields.message);
+    }
+    let matched_filters = options.filters.matched(&fields)?;
+
+    let display = state.resolve_display(&fields, options);
+    let built = (|| -> PyResult<Bound<'_, PyDict>> {
+        let dict = fields_to_dict(py, &fields, &display.display, options)?;
+        if options.display_source {
+            dict.set_item("display_source", display.source)?;
+            if let Some(display_id) = display.display_id {
+                dict.set_item("display_id", display_id)?;
+            }
+        }
+        if options.explain_filter {
+            dict.set_item("matched_filters", matched_filters)?;
+        }
+        apply_transforms(&dict, options)?;
+        Ok(dict)
+    })();
+    match built {
+        Ok(dict) => Some(dict.into()),
+        Err(e) => {
+            state.error.get_or_insert(e);
+            None
+        }
+    }
+}
+
+/// 로그 라인을 파싱하여 딕셔너리로 반환
+#[pyfunction]
+#[pyo3(signature = (line, **options))]
+fn parse_log_line(py: Python<'_>, line: &str, options: Option<&Bound<'_, PyDict>>) -> PyResult<Option<PyObject>> {
+    let options = ParseOptions::from_kwargs(options)?;
+    let mut state = ParseState::default();
+    let entry = parse_entry(py, line, &options, &mut state);
+    state.take_error()?;
+    Ok(entry.map(|entry| output_entry(py, entry, &options)))
+}
+
+/// 배치 파싱 (벡터화된 처리로 더 빠름)
+/// 정규식 매칭은 GIL을 놓고 rayon으로 병렬 수행, dict 생성은 입력 순서대로 GIL 안에서 수행
+/// num_threads: 매칭 스레드 수 (0이면 rayon 전역 풀 = 전체 코어)
+#[pyfunction]
+#[pyo3(signature = (lines, num_threads=0, **options))]
+fn parse_log_batch(
+    py: Python<'_>,
+    lines: Vec<String>,
+    num_threads: usize,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Vec<PyObject>> {
+    let options = ParseOptions::from_kwargs(options)?;
+    parse_lines_parallel(py, &lines, num_threads, &options)
+}
+
+/// 줄 목록을 병렬 매칭 후 순서대로 dict 변환 (parse_log_batch, LogParser.parse_batch)
+fn parse_lines_parallel(py: Python<'_>, lines: &[String], num_threads: usize, options: &ParseOptions) -> PyResult<Vec<PyObject>> {
+    let formats = options.formats;
+    let match_all = || -> Vec<Result<Option<LogFields<'_>>, RegexTimeout>> {
+        lines.par_iter().map(|line| match_entry_fields(line, formats)).collect()
+    };
+    let matched = py.allow_threads(|| {
+        if num_threads == 0 {
+            return Ok(match_all());
+        }
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .build()
+            .map(|pool| pool.install(match_all))
+            .map_err(|e| e.to_string())
+    });
+    let matched = matched.map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to build thread pool: {}", e)))?;
+
+    let mut state = ParseState::default();
+    let results = lines
+        .iter()
+        .zip(matched)
+        .filter_map(|(line, fields)| parse_matched_entry(py, line, fields, options, &mut state))
+        .collect();
+    state.take_error()?;
+    Ok(finish_results(py, results, options))
+}
+
+/// 전체 결과를 한 번에 반환하는 함수들의 마무리 처리 (sort_output, sample_rate/max_rows, sort_by_severity, as_objects)
+fn finish_results(py: Python<'_>, results: Vec<PyObject>, options: &ParseOptions) -> Vec<PyObject> {
+    let results = if options.sort_output {
+        sort_by_timestamp(py, results)
+    } else {
+        results
+    };
+    let results = sample_every(results, options.sample_rate, &mut 0);
+    let results = match options.max_rows {
+        Some(max_rows) => spread_rows(results, max_rows),
+        None => results,
+    };
+    let results = if options.sort_by_severity {
+        sort_by_severity(py, results)
+    } else {
+        results
+    };
+    output_entries(py, results, options)
+}
+
+/// sample_rate: 항목 rate개 중 첫 번째만 남김
+/// seen은 지금까지 본 항목 수 - 스트리밍은 청크가 바뀌어도 간격이 이어지도록 호출 사이에 유지
+fn sample_every(entries: Vec<PyObject>, rate: Option<u32>, seen: &mut usize) -> Vec<PyObject> {
+    let Some(rate) = rate else {
+        return entries;
+    };
+    entries
+        .into_iter()
+        .filter(|_| {
+            let keep = (*seen).is_multiple_of(rate as usize);
+            *seen += 1;
+            keep
+        })
+        .collect()
+}
+
+/// 청크 단위로 내보내는 함수(스트리밍, follow, parse_file_queue)의 호출 사이 출력 상태
+#[derive(Default)]
+struct ChunkOutput {
+    /// 다음 청크에서 갱신될 수 있어 보류한 마지막 항목 (holds_back_last)
+    held_entry: Option<PyObject>,
+    /// sample_rate 간격 - 청크가 바뀌어도 이어짐
+    sampled: usize,
+}
+
+impl ChunkOutput {
+    /// 보류 항목 + 이번 청크 항목 → 콜백에 넘길 항목 (sample_rate 적용)
+    /// is_last가 아니면 갱신될 수 있는 마지막 항목은 다시 보류
+    fn take(&mut self, parsed: impl Iterator<Item = PyObject>, options: &ParseOptions, is_last: bool) -> Vec<PyObject> {
+        let mut entries: Vec<PyObject> = self.held_entry.take().into_iter().collect();
+        entries.extend(parsed);
+        if options.holds_back_last() && !is_last {
+            self.held_entry = entries.pop();
+        }
+        sample_every(entries, options.sample_rate, &mut self.sampled)
+    }
+
+    fn has_held(&self) -> bool {
+        self.held_entry.is_some()
+    }
+}
+
+/// max_rows: 항목이 더 많으면 index k * len / max_rows (k = 0..max_rows)만 남겨 처음부터 끝까지 고르게 분포
+/// 앞부분만 자르지 않으므로 타임라인 개요에 파일 전체 구간이 나타남 (무작위가 아니라 같은 입력이면 같은 결과)
+fn spread_rows(entries: Vec<PyObject>, max_rows: usize) -> Vec<PyObject> {
+    let len = entries.len();
+    if len <= max_rows {
+        return entries;
+    }
+    let mut next = 0usize;
+    entries
+        .into_iter()
+        .enumerate()
+        .filter(|&(index, _)| {
+            let keep = next < max_rows && index == (next as u128 * len as u128 / max_rows as u128) as usize;
+            if keep {
+                next += 1;
+            }
+            keep
+        })
+        .map(|(_, entry)| entry)
+        .collect()
+}
+
+/// 심각한 레벨(A, F, E, ...)이 앞에 오도록 안정 정렬 - 같은 레벨 안에서는 앞 단계의 순서(파일 또는 시간 순) 유지
+/// 레벨 없는 항목("-", keep_unparsed 항목)은 맨 뒤
+fn sort_by_severity(py: Python<'_>, results: Vec<PyObject>) -> Vec<PyObject> {
+    let mut keyed: Vec<(u8, PyObject)> = results
+        .into_iter()
+        .map(|entry| {
+            let severity = entry
+                .bind(py)
+                .downcast::<PyDict>()
+                .ok()
+                .and_then(|dict| dict.get_item("level").ok().flatten())
+                .and_then(|level| level.extract::<String>().ok())
+                .map_or(0, |level| level_num(&level));
+            (severity, entry)
+        })
+        .collect();
+    keyed.sort_by_key(|(severity, _)| std::cmp::Reverse(*severity));
+    keyed.into_iter().map(|(_, entry)| entry).collect()
+}
+
+/// as_objects면 항목 dict를 ParsedLog로 바꿈 (파싱되지 않은 줄의 dict는 그대로)
+fn output_entry(py: Python<'_>, entry: PyObject, options: &ParseOptions) -> PyObject {
+    if options.as_objects {
+        ParsedLog::from_entry(py, entry)
+    } else {
+        entry
+    }
+}
+
+fn output_entries(py: Python<'_>, entries: Vec<PyObject>, options: &ParseOptions) -> Vec<PyObject> {
+    if !options.as_objects {
+        return entries;
+    }
+    entries.into_iter().map(|entry| ParsedLog::from_entry(py, entry)).collect()
+}
+
+/// 스트리밍 콜백에 넘길 청크 - columnar면 entries_to_columns, 아니면 output_entries 리스트
+fn chunk_payload(py: Python<'_>, entries: Vec<PyObject>, options: &ParseOptions) -> PyResult<PyObject> {
+    if options.columnar {
+        entries_to_columns(py, entries)
+    } else {
+        Ok(output_entries(py, entries, options).into_py(py))
+    }
+}
+
+/// 항목 dict 리스트 → {키: 값 리스트} (pandas.DataFrame(columns)로 바로 넘길 수 있는 형태)
+/// 키 순서는 처음 나온 순서, 어떤 항목에 없는 키는 그 자리가 None이라 모든 리스트 길이가 같음
+/// 값은 복사하지 않고 같은 객체를 참조
+fn entries_to_columns(py: Python<'_>, entries: Vec<PyObject>) -> PyResult<PyObject> {
+    let rows = entries.len();
+    let mut keys: Vec<Bound<'_, PyAny>> = Vec::new();
+    let mut key_index: HashMap<String, usize> = HashMap::new();
+    let mut columns: Vec<Vec<PyObject>> = Vec::new();
+    for (row, entry) in entries.iter().enumerate() {
+        let Ok(dict) = entry.bind(py).downcast::<PyDict>() else {
+            continue;
+        };
+        for (key, value) in dict.iter() {
+            let name: String = key.extract()?;
+            let column = match key_index.get(&name) {
+                Some(&column) => column,
+                None => {
+                    key_index.insert(name, columns.len());
+                    keys.push(key);
+                    columns.push(Vec::with_capacity(rows));
+                    columns.len() - 1
+                }
+            };
+            let column = &mut columns[column];
+            column.resize_with(row, || py.None());
+            column.push(value.unbind());
+        }
+    }
+
+    let result = PyDict::new_bound(py);
+    for (key, mut column) in keys.into_iter().zip(columns) {
+        column.resize_with(rows, || py.None());
+        result.set_item(key, column)?;
+    }
+    Ok(result.into())
+}
+
+/// `timestamp` 필드의 나노초 값으로 안정 정렬 (-v nsec 캡처의 같은 밀리초 안 순서도 유지)
+/// - 같은 시각이면 원래 순서 유지
+/// - 타임스탬프를 해석할 수 없는 항목은 직전 항목과 같은 키를 받아 바로 뒤에 붙어 있음
+/// - 연도가 없으므로 12-31 → 01-01로 넘어가는 캡처는 01-01이 앞으로 정렬됨
+///
+/// 정렬 키(i64)만 추가로 잡으므로 메모리는 항목 수 × 16바이트 정도 더 사용
+fn sort_by_timestamp(py: Python<'_>, results: Vec<PyObject>) -> Vec<PyObject> {
+    let mut last_key = i64::MIN;
+    let mut keyed: Vec<(i64, PyObject)> = results
+        .into_iter()
+        .map(|entry| {
+            let key = entry
+                .bind(py)
+                .downcast::<PyDict>()
+                .ok()
+                .and_then(|dict| dict.get_item("timestamp").ok().flatten())
+                .and_then(|timestamp| timestamp.extract::<String>().ok())
+                .and_then(|timestamp| timestamp_to_nanos(&timestamp));
+            if let Some(key) = key {
+                last_key = key;
+            }
+            (last_key, entry)
+        })
+        .collect();
+
+    // sort_by_key는 안정 정렬
+    keyed.sort_by_key(|(key, _)| *key);
+    keyed.into_iter().map(|(_, entry)| entry).collect()
+}
+
+/// 파일에서 로그를 읽고 파싱 (고성능 파일 I/O + 파싱)
+/// 배치 단위로 결과를 반환하여 메모리 효율적 처리
+///
+/// on_error(line_number, message)를 주면 줄 읽기 오류(잘못된 UTF-8, 디코딩 실패, I/O 오류)마다 호출
+/// (줄 번호는 1부터). 콜백이 예외를 던지면 파싱을 멈추고 그 예외를 전달
+///
+/// time_budget_ms가 있으면 그 시간 안에서만 파싱하고 (entries, stopped_at_offset) 반환 (UI가 멈추지 않도록 나눠 파싱)
+/// stopped_at_offset은 다음에 읽을 줄의 바이트 오프셋(parse_from_offset으로 이어서 파싱), 끝까지 읽었으면 None
+/// 바이트 오프셋을 쓰므로 이때는 gzip·UTF-16 BOM 파일과 encoding 옵션이 ValueError
+#[pyfunction]
+#[pyo3(signature = (file_path, batch_size, on_error=None, time_budget_ms=None, **options))]
+fn parse_log_file_chunk(
+    py: Python<'_>,
+    file_path: &str,
+    batch_size: usize,
+    on_error: Option<PyObject>,
+    time_budget_ms: Option<u64>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<PyObject> {
+    let options = ParseOptions::from_kwargs(options)?;
+    if let Some(budget_ms) = time_budget_ms {
+        let (results, stopped_at) = parse_with_budget(py, file_path, 0, Some(budget_ms), &options, on_error.as_ref())?;
+        return Ok((results, stopped_at).into_py(py));
+    }
+    Ok(parse_file_entries(py, file_path, batch_size, &options, None, on_error.as_ref())?.into_py(py))
+}
+
+/// 줄 읽기 오류 보고 - on_error가 있으면 on_error(line_number, message), 없으면 stderr
+fn report_read_error(py: Python<'_>, on_error: Option<&PyObject>, line_number: usize, error: &dyn std::fmt::Display) -> PyResult<()> {
+    match on_error {
+        Some(on_error) => on_error.call1(py, (line_number, error.to_string())).map(|_| ()),
+        None => {
+            eprintln!("Line read error: {}", error);
+            Ok(())
+        }
+    }
+}
+
+/// parse_log_file_stats의 줄 분류 집계
+#[derive(Default)]
+struct FileStats {
+    total_lines: usize,
+    parsed: usize,
+    unparsed: usize,
+    blank: usize,
+    read_errors: usize,
+    per_level: HashMap<String, usize>,
+    per_display: HashMap<String, usize>,
+}
+
+impl FileStats {
+    /// 반환된 항목의 level/display 집계 (변환으로 키가 바뀌었거나 keep_unparsed 항목이면 건너뜀)
+    fn count_entry(&mut self, py: Python<'_>, entry: &PyObject) {
+        let Ok(dict) = entry.bind(py).downcast::<PyDict>() else {
+            return;
+        };
+        for (key, counts) in [("level", &mut self.per_level), ("display", &mut self.per_display)] {
+            if let Some(value) = dict.get_item(key).ok().flatten().and_then(|value| value.extract::<String>().ok()) {
+                *counts.entry(value).or_default() += 1;
+            }
+        }
+    }
+
+    fn to_dict(&self, py: Python<'_>, entries: usize) -> PyResult<PyObject> {
+        let dict = PyDict::new_bound(py);
+        dict.set_item("total_lines", self.total_lines)?;
+        dict.set_item("parsed", self.parsed)?;
+        dict.set_item("unparsed", self.unparsed)?;
+        dict.set_item("blank", self.blank)?;
+        dict.set_item("read_errors", self.read_errors)?;
+        dict.set_item("entries", entries)?;
+        dict.set_item("per_level", self.per_level.clone())?;
+        dict.set_item("per_display", self.per_display.clone())?;
+        Ok(dict.into())
+    }
+}
+
+/// 파일 전체를 읽어 batch_size줄씩 파싱한 결과 (parse_log_file_chunk, LogParser.parse_file)
+/// stats가 있으면 같은 패스에서 줄 분류와 level/display별 항목 수를 집계
+fn parse_file_entries(
+    py: Python<'_>,
+    file_path: &str,
+    batch_size: usize,
+    options: &ParseOptions,
+    mut stats: Option<&mut FileStats>,
+    on_error: Option<&PyObject>,
+) -> PyResult<Vec<PyObject>> {
+    // 파일 읽기 (GIL 밖에서 수행)
+    let reader = open_log_reader_as(file_path, options.encoding)?;
+    let mut lines = Vec::new();
+    
+    for (index, line) in limited_lines(reader, options.max_line_length).enumerate() {
+        if let Some(stats) = stats.as_deref_mut() {
+            stats.total_lines += 1;
+        }
+        match line {
+            Ok(line) => {
+                let trimmed = line.trim();
+                if !trimmed.is_empty() {
+                    lines.push(trimmed.to_string());  // 소유권 확보
+                } else if let Some(stats) = stats.as_deref_mut() {
+                    stats.blank += 1;
+                }
+            }
+            Err(e) => {
+                // 읽기 오류는 보고하고 계속 진행
+                report_read_error(py, on_error, index + 1, &e)?;
+                if let Some(stats) = stats.as_deref_mut() {
+                    stats.read_errors += 1;
+                }
+                // 잘못된 UTF-8 줄만 건너뛸 수 있음. 그 외(잘린 gzip 등)는 같은 오류가 반복되므로 여기서 끝냄
+                if e.kind() != std::io::ErrorKind::InvalidData {
+                    break;
+                }
+            }
+        }
+    }
+    
+    // 파싱 (GIL 필요)
+    let mut results = Vec::new();
+    let mut state = ParseState::default();
+
+    // 배치 단위로 파싱
+    for chunk in lines.chunks(batch_size.max(1)) {
+        let parsed: Vec<PyObject> = match stats.as_deref_mut() {
+            None => chunk
+                .iter()
+                .filter_map(|l| parse_entry(py, l, options, &mut state))
+                .collect(),
+            Some(stats) => chunk
+                .iter()
+                .filter_map(|l| {
+                    let fields = match_entry_fields(l, options.formats);
+                    if matches!(fields, Ok(Some(_))) {
+                        stats.parsed += 1;
+                    } else {
+                        stats.unparsed += 1;
+                    }
+                    let entry = parse_matched_entry(py, l, fields, options, &mut state)?;
+                    stats.count_entry(py, &entry);
+                    Some(entry)
+                })
+                .collect(),
+        };
+        state.take_error()?;
+        results.extend(parsed);
+    }
+
+    Ok(finish_results(py, results, options))
+}
+
+/// parse_log_file_chunk와 같은 결과를 메모리 매핑으로 읽어 반환
+/// 줄마다 String을 만들지 않고 매핑된 바이트의 줄 슬라이스를 그대로 파싱
+/// 매핑할 수 없는 파일(gzip, BOM, encoding 옵션)이나 mmap 기능이 꺼진 빌드는 parse_log_file_chunk와 같은 경로
+/// on_error(line_number, message): parse_log_file_chunk와 같음
+#[pyfunction]
+#[pyo3(signature = (file_path, on_error=None, **options))]
+fn parse_file_mmap(
+    py: Python<'_>,
+    file_path: &str,
+    on_error: Option<PyObject>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Vec<PyObject>> {
+    let options = ParseOptions::from_kwargs(options)?;
+    if options.encoding.is_none() {
+        let parsed = with_mapped_file(file_path, |bytes| -> PyResult<Vec<PyObject>> {
+            let mut results = Vec::new();
+            let mut state = ParseState::default();
+            for (index, raw) in bytes.split(|&b| b == b'\n').enumerate() {
+                if line_too_long(raw.strip_suffix(b"\r").unwrap_or(raw), options.max_line_length) {
+                    report_read_error(py, on_error.as_ref(), index + 1, &line_too_long_error(options.max_line_length))?;
+                    continue;
+                }
+                match std::str::from_utf8(raw) {
+                    Ok(line) => {
+                        let trimmed = line.trim();
+                        if !trimmed.is_empty() {
+                            results.extend(parse_entry(py, trimmed, &options, &mut state));
+                            state.take_error()?;
+                        }
+                    }
+                    Err(e) => report_read_error(py, on_error.as_ref(), index + 1, &e)?,
+                }
+            }
+            Ok(results)
+        })?;
+        if let Some(results) = parsed {
+            return Ok(finish_results(py, results?, &options));
+        }
+    }
+    parse_file_entries(py, file_path, 10000, &options, None, on_error.as_ref())
+}
+
+/// parse_log_file_chunk와 같은 결과에 줄 통계를 함께 반환 → (entries, stats)
+/// stats: total_lines / parsed(형식이 맞은 줄, 필터로 걸러진 줄 포함) / unparsed / blank / read_errors,
+/// entries(반환 항목 수), per_level / per_display(반환 항목 기준)
+#[pyfunction]
+#[pyo3(signature = (file_path, batch_size=10000, **options))]
+fn parse_log_file_stats(
+    py: Python<'_>,
+    file_path: &str,
+    batch_size: usize,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<(Vec<PyObject>, PyObject)> {
+    let options = ParseOptions::from_kwargs(options)?;
+    let mut stats = FileStats::default();
+    let results = parse_file_entries(py, file_path, batch_size, &options, Some(&mut stats), None)?;
+    let stats = stats.to_dict(py, results.len())?;
+    Ok((results, stats))
+}
+
+/// as_objects 옵션의 항목 - dict 키 조회 대신 속성으로 접근 (dict보다 작고 빠름)
+/// 기본 7개 필드 외의 키(uid, event_type 등 옵션이 추가한 것)는 extra dict에 들어감
+#[pyclass(frozen, module = "logcat_parser_rs")]
+struct ParsedLog {
+    #[pyo3(get)]
+    timestamp: String,
+    #[pyo3(get)]
+    level: String,
+    #[pyo3(get)]
+    pid: String,
+    #[pyo3(get)]
+    tid: String,
+    #[pyo3(get)]
+    tag: String,
+    #[pyo3(get)]
+    message: String,
+    #[pyo3(get)]
+    display: String,
+    extra: Py<PyDict>,
+}
+
+impl ParsedLog {
+    const FIELDS: [&'static str; 7] = ["timestamp", "level", "pid", "tid", "tag", "message", "display"];
+
+    /// 항목 dict에서 기본 필드를 꺼내고 나머지 키는 그 dict를 extra로 재사용
+    fn from_entry(py: Python<'_>, entry: PyObject) -> PyObject {
+        let Ok(dict) = entry.bind(py).downcast::<PyDict>() else {
+            return entry;
+        };
+        if !dict.contains("timestamp").unwrap_or(false) {
+            // keep_unparsed의 {"raw", "parsed"} 항목
+            return entry;
+        }
+
+        let mut values = Self::FIELDS.map(|key| {
+            let value = dict.get_item(key).ok().flatten().and_then(|value| value.extract::<String>().ok());
+            if value.is_some() {
+                let _ = dict.del_item(key);
+            }
+            value.unwrap_or_default()
+        });
+        let log = Self {
+            timestamp: std::mem::take(&mut values[0]),
+            level: std::mem::take(&mut values[1]),
+            pid: std::mem::take(&mut values[2]),
+            tid: std::mem::take(&mut values[3]),
+            tag: std::mem::take(&mut values[4]),
+            message: std::mem::take(&mut values[5]),
+            display: std::mem::take(&mut values[6]),
+            extra: dict.clone().unbind(),
+        };
+        match Py::new(py, log) {
+            Ok(log) => log.into_py(py),
+            Err(_) => entry,
+        }
+    }
+}
+
+#[pymethods]
+impl ParsedLog {
+    /// 기본 필드 외에 옵션이 추가한 키 (없으면 빈 dict)
+    #[getter]
+    fn extra(&self, py: Python<'_>) -> Py<PyDict> {
+        self.extra.clone_ref(py)
+    }
+
+    /// dict 출력과 같은 모양의 dict로 변환
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new_bound(py);
+        let values = [&self.timestamp, &self.level, &self.pid, &self.tid, &self.tag, &self.message, &self.display];
+        for (key, value) in Self::FIELDS.iter().zip(values) {
+            dict.set_item(key, value)?;
+        }
+        dict.update(self.extra.bind(py).as_mapping())?;
+        Ok(dict)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ParsedLog(timestamp={:?}, level={:?}, pid={:?}, tid={:?}, tag={:?}, message={:?}, display={:?})",
+            self.timestamp, self.level, self.pid, self.tid, self.tag, self.message, self.display
+        )
+    }
+}
+
+/// 옵션을 한 번 해석해 들고 있는 파서 - 설정이 다른 파서 여럿을 동시에 쓸 때
+/// 생성자는 parse_* 함수와 같은 **options를 받음 (min_level, display_map, formats, keep_unparsed 등)
+/// 상태(merge_*, display_sticky)는 호출마다 새로 시작
+/// 변환은 인스턴스별 (LogParser.add_transform), 사용자 형식(add_custom_format), 조합 규칙(set_composite_display_rules),
+/// 전역 PID 맵은 모듈 전역이라 모든 LogParser 인스턴스와 parse_* 함수가 함께 씀
+#[pyclass]
+struct LogParser {
+    options: ParseOptions,
+}
+
+#[pymethods]
+impl LogParser {
+    #[new]
+    #[pyo3(signature = (**options))]
+    fn new(options: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+        Ok(Self {
+            options: ParseOptions::from_kwargs(options)?,
+        })
+    }
+
+    fn parse_line(&self, py: Python<'_>, line: &str) -> PyResult<Option<PyObject>> {
+        let mut state = ParseState::default();
+        let entry = parse_entry(py, line, &self.options, &mut state);
+        state.take_error()?;
+        Ok(entry.map(|entry| output_entry(py, entry, &self.options)))
+    }
+
+    #[pyo3(signature = (lines, num_threads=0))]
+    fn parse_batch(&self, py: Python<'_>, lines: Vec<String>, num_threads: usize) -> PyResult<Vec<PyObject>> {
+        parse_lines_parallel(py, &lines, num_threads, &self.options)
+    }
+
+    #[pyo3(signature = (file_path, batch_size=10000))]
+    fn parse_file(&self, py: Python<'_>, file_path: &str, batch_size: usize) -> PyResult<Vec<PyObject>> {
+        parse_file_entries(py, file_path, batch_size, &self.options, None, None)
+    }
+
+    /// 이 파서의 결과에만 적용할 변환 등록 (등록 순서대로 적용), 이 파서에 등록된 변환 개수 반환
+    /// op/인자는 모듈 함수 add_transform과 같음: rename, drop_field, add_constant, regex_extract
+    #[pyo3(signature = (op, **params))]
+    fn add_transform(&mut self, py: Python<'_>, op: &str, params: Option<&Bound<'_, PyDict>>) -> PyResult<usize> {
+        let transform = Transform::from_params(op, params)?;
+        let mut transforms: Vec<Transform> = self.options.transforms.iter().map(|t| t.clone_ref(py)).collect();
+        transforms.push(transform);
+        self.options.transforms = Arc::new(transforms);
+        Ok(self.options.transforms.len())
+    }
+
+    /// 이 파서에 등록된 변환 모두 제거
+    fn clear_transforms(&mut self) {
+        self.options.transforms = Arc::default();
+    }
+}
+
+// 시간 예산 확인 간격 (줄 수) - 매 줄 시계를 읽지 않도록
+const BUDGET_CHECK_INTERVAL: usize = 1024;
+
+/// 오프셋을 원본 바이트 위치로 쓰는 함수용으로 파일을 열기
+/// gzip·UTF-16 BOM 파일, encoding 옵션은 오프셋이 원본 바이트와 맞지 않으므로 ValueError
+/// 반환: (reader, 본문 시작 오프셋 - UTF-8 BOM이면 BOM 다음)
+fn open_plain_reader(file_path: &str, encoding: Option<&'static Encoding>) -> PyResult<(BufReader<File>, u64)> {
+    let file = File::open(file_path)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("Failed to open file: {}", e)))?;
+    let mut reader = BufReader::new(file);
+    let head = reader.fill_buf()?;
+    let bom = Encoding::for_bom(head);
+    if encoding.is_some() || head.starts_with(&[0x1f, 0x8b]) || bom.is_some_and(|(found, _)| found != encoding_rs::UTF_8) {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "byte offsets require an uncompressed UTF-8 file (gzip, UTF-16 BOM and encoding are not supported)",
+        ));
+    }
+    Ok((reader, bom.map_or(0, |(_, len)| len as u64)))
+}
+
+/// start_offset부터 한 줄씩 읽으며 파싱, 예산을 넘기면 다음 줄의 오프셋과 함께 중단
+/// 반환: (결과, 중단 오프셋 - 끝까지 읽었으면 None)
+fn parse_with_budget(
+    py: Python<'_>,
+    file_path: &str,
+    start_offset: u64,
+    budget_ms: Option<u64>,
+    options: &ParseOptions,
+    on_error: Option<&PyObject>,
+) -> PyResult<(Vec<PyObject>, Option<u64>)> {
+    let (mut reader, body_start) = open_plain_reader(file_path, options.encoding)?;
+    let start_offset = start_offset.max(body_start);
+    reader
+        .seek(SeekFrom::Start(start_offset))
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("Failed to seek file: {}", e)))?;
+
+    let start = Instant::now();
+    let budget = budget_ms.map(Duration::from_millis);
+    let mut buf = Vec::new();
+    let mut offset = start_offset;
+    let mut results = Vec::new();
+    let mut state = ParseState::default();
+    let mut line_count = 0usize;
+
+    loop {
+        if let Some(budget) = budget {
+            if line_count > 0 && line_count.is_multiple_of(BUDGET_CHECK_INTERVAL) && start.elapsed() >= budget {
+                return Ok((finish_results(py, results, options), Some(offset)));
+            }
+        }
+
+        buf.clear();
+        let read = match read_line_limited(&mut reader, &mut buf, options.max_line_length) {
+            Ok(read) => read,
+            Err(e) => {
+                report_read_error(py, on_error, line_count + 1, &e)?;
+                return Err(PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("Failed to read file: {}", e)));
+            }
+        };
+        if read == 0 {
+            break;
+        }
+        offset += read as u64;
+        line_count += 1;
+        if line_too_long(&buf, options.max_line_length) {
+            report_read_error(py, on_error, line_count, &line_too_long_error(options.max_line_length))?;
+            continue;
+        }
+
+        match std::str::from_utf8(&buf) {
+            Ok(line) => {
+                let trimmed = line.trim();
+                if !trimmed.is_empty() {
+                    results.extend(parse_entry(py, trimmed, options, &mut state));
+                    state.take_error()?;
+                }
+            }
+            Err(e) => report_read_error(py, on_error, line_count, &e)?,
+        }
+    }
+
+    Ok((finish_results(py, results, options), None))
+}
+
+/// 바이트 오프셋부터 파싱 (parse_log_file_chunk(time_budget_ms=...)의 결과를 이어서 파싱)
+/// offset은 줄의 시작이어야 함 (이전 호출이 돌려준 stopped_at_offset, 또는 0)
+/// 반환: (entries, stopped_at_offset) - 예산 없이 끝까지 읽거나 다 읽었으면 stopped_at_offset은 None
+/// parse_log_file_chunk(time_budget_ms=...)와 같이 gzip·UTF-16 BOM 파일과 encoding 옵션은 ValueError
+#[pyfunction]
+#[pyo3(signature = (file_path, offset, time_budget_ms=None, **options))]
+fn parse_from_offset(
+    py: Python<'_>,
+    file_path: &str,
+    offset: u64,
+    time_budget_ms: Option<u64>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<(Vec<PyObject>, Option<u64>)> {
+    let options = ParseOptions::from_kwargs(options)?;
+    parse_with_budget(py, file_path, offset, time_budget_ms, &options, None)
+}
+
+/// parse_file_queue의 Python 이터레이터 - 배치(dict 리스트)를 하나씩 꺼냄
+/// 읽기 스레드가 원본 줄 배치를 bounded 채널에 넣고, 파싱(dict 생성)은 꺼낼 때 GIL 안에서 수행
+#[pyclass]
+struct BatchIterator {
+    receiver: crossbeam_channel::Receiver<LineBatch>,
+    options: ParseOptions,
+    state: ParseState,
+    on_error: Option<PyObject>,
+    /// 배치 사이 보류 항목·sample_rate 간격
+    output: ChunkOutput,
+}
+
+/// parse_file_queue 읽기 스레드가 넘기는 원본 줄 배치
+struct LineBatch {
+    lines: Vec<String>,
+    /// 이 배치를 읽는 동안 건너뛴 줄 (줄 번호, 오류)
+    skipped: Vec<(usize, std::io::Error)>,
+    /// 읽기를 멈춘 오류 (마지막 배치에만 있음)
+    fatal: Option<(usize, std::io::Error)>,
+}
+
+#[pymethods]
+impl BatchIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    /// 다음 배치. 모두 걸러진 배치는 건너뛰고, 파일 끝이면 StopIteration
+    /// 읽기 스레드가 만난 오류는 그 배치를 꺼낼 때 report_read_error로 보고
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<Vec<PyObject>>> {
+        loop {
+            // 읽기 스레드를 기다리는 동안 GIL을 놓음
+            let receiver = &self.receiver;
+            let Ok(batch) = py.allow_threads(|| receiver.recv()) else {
+                // 파일 끝 - 보류 항목이 있으면 마지막 배치로 전달
+                let last = self.output.take(std::iter::empty(), &self.options, true);
+                return Ok((!last.is_empty()).then(|| output_entries(py, last, &self.options)));
+            };
+            for (line_number, error) in batch.skipped.iter().chain(&batch.fatal) {
+                report_read_error(py, self.on_error.as_ref(), *line_number, error)?;
+            }
+            let (options, state) = (&self.options, &mut self.state);
+            let parsed = self.output.take(
+                batch.lines.iter().filter_map(|line| parse_entry(py, line, options, state)),
+                options,
+                false,
+            );
+            self.state.take_error()?;
+            if !parsed.is_empty() {
+                return Ok(Some(output_entries(py, parsed, &self.options)));
+            }
+        }
+    }
+}
+
+/// 큰 파일을 메모리 상한 안에서 배치 단위로 꺼내 쓰는 이터레이터 반환 (parse_log_file_chunk는 전체 결과를 들고 있음)
+/// 읽기 스레드가 batch_size줄씩 최대 queue_depth개 배치만 채널에 쌓아 두고,
+/// 소비자가 느리면 채널이 찰 때마다 읽기를 멈춤 (백프레셔)
+/// 이터레이터를 버리면 읽기 스레드도 다음 배치를 넣으려다 종료
+/// on_error(line_number, message): parse_log_file_chunk와 같음 (이터레이터를 돌리는 쪽에서 호출)
+#[pyfunction]
+#[pyo3(signature = (file_path, batch_size, queue_depth=4, on_error=None, **options))]
+fn parse_file_queue(
+    file_path: &str,
+    batch_size: usize,
+    queue_depth: usize,
+    on_error: Option<PyObject>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<BatchIterator> {
+    let options = ParseOptions::from_kwargs(options)?;
+    options.check_streaming()?;
+    let file = open_log_reader_as(file_path, options.encoding)?;
+
+    let batch_size = batch_size.max(1);
+    let max_line_length = options.max_line_length;
+    let (sender, receiver) = crossbeam_channel::bounded(queue_depth.max(1));
+    std::thread::spawn(move || {
+        let mut lines = read_lines(file, max_line_length);
+        let mut batch = Vec::with_capacity(batch_size);
+        while let Some(line) = lines.next() {
+            let trimmed = line.trim();
+            if !trimmed.is_empty() {
+                batch.push(trimmed.to_string());
+            }
+            if batch.len() >= batch_size {
+                let full = LineBatch {
+                    lines: std::mem::replace(&mut batch, Vec::with_capacity(batch_size)),
+                    skipped: std::mem::take(&mut lines.skipped),
+                    fatal: None,
+                };
+                if sender.send(full).is_err() {
+                    // 이터레이터가 사라짐
+                    return;
+                }
+            }
+        }
+        let last = LineBatch {
+            lines: batch,
+            skipped: std::mem::take(&mut lines.skipped),
+            fatal: lines.fatal.take(),
+        };
+        if !last.lines.is_empty() || !last.skipped.is_empty() || last.fatal.is_some() {
+            let _ = sender.send(last);
+        }
+    });
+
+    Ok(BatchIterator {
+        receiver,
+        options,
+        state: ParseState::default(),
+        on_error,
+        output: ChunkOutput::default(),
+    })
+}
+
+// 월별 누적 일수 (윤년 기준 - 02-29도 순서가 어긋나지 않도록)
+const DAYS_BEFORE_MONTH: [i64; 12] = [0, 31, 60, 91, 121, 152, 182, 213, 244, 274, 305, 335];
+// 월별 일수 (연도를 모르므로 2월은 29일까지 허용)
+const DAYS_IN_MONTH: [i64; 12] = [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+
+/// `MM-DD HH:MM:SS.mmm` → 해당 연도 1월 1일 0시 기준 밀리초
+/// logcat 타임스탬프에는 연도가 없으므로 같은 연도 안에서의 정렬·차이 계산용
+/// 소수부가 6/9자리(-v usec, -v nsec)면 밀리초 아래는 버림
+/// -v epoch 타임스탬프(`1700000000.123`)는 1970년 기준 절대 밀리초 (형식이 섞이지 않는 한 정렬·차이 계산은 같음)
+fn timestamp_to_millis(timestamp: &str) -> Option<i64> {
+    if let Some(millis) = epoch_to_millis(timestamp) {
+        return Some(millis);
+    }
+    let parts = TimeParts::parse(timestamp)?;
+    let days = DAYS_BEFORE_MONTH[(parts.month - 1) as usize] + parts.day - 1;
+    Some(days * MILLIS_PER_DAY + parts.millis_of_day())
+}
+
+const MILLIS_PER_DAY: i64 = 24 * 60 * 60 * 1000;
+
+/// 타임스탬프의 날짜/시각 성분 (time_parts 옵션)
+struct TimeParts {
+    month: i64,
+    day: i64,
+    hour: i64,
+    minute: i64,
+    second: i64,
+    millis: i64,
+}
+
+impl TimeParts {
+    /// `MM-DD HH:MM:SS.mmm` 분해 (-v epoch 형식이면 None)
+    /// 범위 밖 성분(13월, 02-30, 24시, 60분/초)도 None - validate, start_time/end_time, ts_ms가 같은 기준을 씀
+    fn parse(timestamp: &str) -> Option<Self> {
+        let bytes = timestamp.as_bytes();
+        let num = |range: std::ops::Range<usize>| -> Option<i64> {
+            let part = timestamp.get(range)?;
+            if part.bytes().all(|b| b.is_ascii_digit()) {
+                part.parse().ok()
+            } else {
+                None
+            }
+        };
+
+        let month = num(0..2)?;
+        let day = num(3..5)?;
+        // 날짜와 시간 사이 공백은 여러 개일 수 있음
+        let time_start = 5 + bytes.get(5..)?.iter().take_while(|b| b.is_ascii_whitespace()).count();
+        let hour = num(time_start..time_start + 2)?;
+        let minute = num(time_start + 3..time_start + 5)?;
+        let second = num(time_start + 6..time_start + 8)?;
+        let millis = num(time_start + 9..time_start + 12)?;
+
+        if !(1..=12).contains(&month) || !(1..=DAYS_IN_MONTH[(month - 1) as usize]).contains(&day) {
+            return None;
+        }
+        if !(0..24).contains(&hour) || !(0..60).contains(&minute) || !(0..60).contains(&second) {
+            return None;
+        }
+        Some(Self {
+            month,
+            day,
+            hour,
+            minute,
+            second,
+            millis,
+        })
+    }
+
+    /// 1970년 기준 밀리초 → UTC 기준 성분 (-v epoch 형식)
+    fn from_epoch_millis(epoch_millis: i64) -> Self {
+        let (_, month, day) = civil_from_days(epoch_millis.div_euclid(MILLIS_PER_DAY));
+        let of_day = epoch_millis.rem_euclid(MILLIS_PER_DAY);
+        Self {
+            month,
+            day,
+            hour: of_day / 3_600_000,
+            minute: of_day / 60_000 % 60,
+            second: of_day / 1000 % 60,
+            millis: of_day % 1000,
+        }
+    }
+
+    fn millis_of_day(&self) -> i64 {
+        ((self.hour * 60 + self.minute) * 60 + self.second) * 1000 + self.millis
+    }
+
+    /// year년으로 가정한 1970년 기준 밀리초 (시간대 없이 UTC로 계산)
+    fn epoch_millis(&self, year: i64) -> i64 {
+        days_from_civil(year, self.month, self.day) * MILLIS_PER_DAY + self.millis_of_day()
+    }
+}
+
+/// 그레고리력 날짜 → 1970-01-01 기준 일 수
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// 1970-01-01 기준 일 수 → (연, 월, 일)
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// `초.소수부` epoch 타임스탬프 → 1970년 기준 밀리초 (MM-DD 형식이면 None)
+fn epoch_to_millis(timestamp: &str) -> Option<i64> {
+    let (seconds, fraction) = timestamp.split_once('.')?;
+    let is_digits = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+    if !is_digits(seconds) || !is_digits(fraction) || fraction.len() < 3 {
+        return None;
+    }
+    Some(seconds.parse::<i64>().ok()? * 1000 + fraction[..3].parse::<i64>().ok()?)
+}
+
+/// `MM-DD HH:MM:SS.fff[fff[fff]]` → 해당 연도 1월 1일 0시 기준 나노초
+/// 소수부는 자릿수에 맞게 환산 (3자리 .123 → 123_000_000ns, 9자리 .123456789 → 그대로)
+fn timestamp_to_nanos(timestamp: &str) -> Option<i64> {
+    let millis = timestamp_to_millis(timestamp)?;
+    let fraction = &timestamp[timestamp.rfind('.')? + 1..];
+    let digits = fraction.bytes().take_while(|b| b.is_ascii_digit()).count().min(9);
+    // 밀리초 아래 자릿수 (최대 6자리) → 나노초
+    let sub_millis = match fraction.get(3..digits) {
+        Some(sub) if !sub.is_empty() => sub.parse::<i64>().ok()? * 10i64.pow(9 - digits as u32),
+        _ => 0,
+    };
+    Some(millis * 1_000_000 + sub_millis)
+}
+
+/// 로그 레벨 → Android 우선순위 번호 (V=2, D=3, I=4, W=5, E=6, F=7, A=8, 레벨 없음 "-"=0)
+fn level_num(level: &str) -> u8 {
+    match level {
+        "V" => 2,
+        "D" => 3,
+        "I" => 4,
+        "W" => 5,
+        "E" => 6,
+        "F" => 7,
+        "A" => 8,
+        _ => 0,
+    }
+}
+
+/// 레벨 심각도 (level_num과 같은 순서: V=2 < D < I < W < E < F < A=8, "-"·알 수 없는 값=0)
+/// 대소문자 무시. min_level 필터, sort_by_severity, 컬럼 출력의 level_num과 같은 기준
+#[pyfunction]
+fn level_severity(level: &str) -> i32 {
+    level_num(&level.trim().to_ascii_uppercase()) as i32
+}
+
+/// 레벨 문자 → 전체 이름 (다른 로그 소스와 스키마를 맞추기 위함, 알 수 없는 값과 '-'는 NONE)
+fn level_full_name(level: &str) -> &'static str {
+    match level {
+        "V" => "VERBOSE",
+        "D" => "DEBUG",
+        "I" => "INFO",
+        "W" => "WARN",
+        "E" => "ERROR",
+        "F" => "FATAL",
+        "A" => "ASSERT",
+        _ => "NONE",
+    }
+}
+
+/// 숫자 필드 파싱 ("-" 등 숫자가 아니면 -1)
+fn numeric_field(value: &str) -> i64 {
+    value.parse().unwrap_or(-1)
+}
+
+/// 수치 배열을 array.array로 변환 (버퍼 프로토콜 지원 - numpy.frombuffer로 복사 없이 사용 가능)
+fn to_py_array<'py, const N: usize>(
+    py: Python<'py>,
+    typecode: &str,
+    values: impl Iterator<Item = [u8; N]>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let bytes: Vec<u8> = values.flatten().collect();
+    let array = py.import_bound("array")?.getattr("array")?.call1((typecode,))?;
+    array.call_method1("frombytes", (PyBytes::new_bound(py, &bytes),))?;
+    Ok(array)
+}
+
+/// 수치 분석용 배치 파싱 - 행(dict) 대신 열 배열을 반환
+///
+/// 반환 dict (파싱 성공한 줄만, 모든 배열 길이 동일):
+/// - "index": int64 - 입력 리스트에서의 줄 번호
+/// - "pid", "tid": int64 - 숫자가 아니면("-") -1
+/// - "level_num": uint8 - V=2, D=3, I=4, W=5, E=6, F=7, A=8, 레벨 없음=0
+/// - "ts_ms": int64 - 연도 1월 1일 0시 기준 밀리초, 변환 불가 시 -1
+/// - "tag", "message": str 리스트
+///
+/// 수치 배열은 array.array('q' / 'B')이므로 numpy.frombuffer(arr, dtype=np.int64 / np.uint8)로 바로 사용
+/// 정규식 파싱은 GIL을 놓고 수행
+#[pyfunction]
+fn parse_batch_numeric(py: Python<'_>, lines: Vec<String>) -> PyResult<PyObject> {
+    struct Columns {
+        index: Vec<i64>,
+        pid: Vec<i64>,
+        tid: Vec<i64>,
+        level_num: Vec<u8>,
+        ts_ms: Vec<i64>,
+        tag: Vec<String>,
+        message: Vec<String>,
+    }
+
+    let columns = py.allow_threads(|| {
+        let mut columns = Columns {
+            index: Vec::with_capacity(lines.len()),
+            pid: Vec::with_capacity(lines.len()),
+            tid: Vec::with_capacity(lines.len()),
+            level_num: Vec::with_capacity(lines.len()),
+            ts_ms: Vec::with_capacity(lines.len()),
+            tag: Vec::with_capacity(lines.len()),
+            message: Vec::with_capacity(lines.len()),
+        };
+        for (i, line) in lines.iter().enumerate() {
+            if let Some(fields) = parse_line_inner(line) {
+                columns.index.push(i as i64);
+                columns.pid.push(numeric_field(fields.pid));
+                columns.tid.push(numeric_field(fields.tid));
+                columns.level_num.push(level_num(fields.level));
+                columns.ts_ms.push(timestamp_to_millis(fields.timestamp).unwrap_or(-1));
+                columns.tag.push(fields.tag.to_string());
+                columns.message.push(normalize_carriage_returns(fields.message).into_owned());
+            }
+        }
+        columns
+    });
+
+    let dict = PyDict::new_bound(py);
+    dict.set_item("index", to_py_array(py, "q", columns.index.iter().map(|v| v.to_ne_bytes()))?)?;
+    dict.set_item("pid", to_py_array(py, "q", columns.pid.iter().map(|v| v.to_ne_bytes()))?)?;
+    dict.set_item("tid", to_py_array(py, "q", columns.tid.iter().map(|v| v.to_ne_bytes()))?)?;
+    dict.set_item("level_num", to_py_array(py, "B", columns.level_num.iter().map(|v| v.to_ne_bytes()))?)?;
+    dict.set_item("ts_ms", to_py_array(py, "q", columns.ts_ms.iter().map(|v| v.to_ne_bytes()))?)?;
+    dict.set_item("tag", columns.tag)?;
+    dict.set_item("message", columns.message)?;
+    Ok(dict.into())
+}
+
+/// 줄 단위로 순차 읽기용 로그 파일 리더 (GIL 밖 스레드로 넘길 수 있음)
+type LogReader = Box<dyn BufRead + Send>;
+
+/// 로그 파일 열기 - gzip이면 투명하게 압축을 풀어 읽음 (CI가 .gz로 보관하는 캡처용)
+/// 확장자가 아니라 매직 바이트로 판별하며, 일반 파일은 BufReader 그대로
+/// 여러 gzip 멤버를 이어 붙인 파일(logrotate, `cat a.gz b.gz`)도 끝까지 읽음
+/// BOM(UTF-8 / UTF-16 LE·BE)이 있으면 그 인코딩으로 UTF-8로 변환하고 BOM은 제거
+fn open_log_reader(file_path: &str) -> PyResult<LogReader> {
+    open_log_reader_as(file_path, None)
+}
+
+/// encoding이 있으면 BOM이 없어도 그 인코딩으로 읽음 (BOM이 있으면 BOM 우선)
+/// BOM도 encoding도 없으면 바이트 그대로 (UTF-8 파일은 변환하지 않음)
+fn open_log_reader_as(file_path: &str, encoding: Option<&'static Encoding>) -> PyResult<LogReader> {
+    let file = File::open(file_path)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("Failed to open file: {}", e)))?;
+    let mut reader = BufReader::new(file);
+    let mut reader: LogReader = if reader.fill_buf()?.starts_with(&[0x1f, 0x8b]) {
+        Box::new(BufReader::new(flate2::bufread::MultiGzDecoder::new(reader)))
+    } else {
+        Box::new(reader)
+    };
+
+    if encoding.is_none() && Encoding::for_bom(reader.fill_buf()?).is_none() {
+        return Ok(reader);
+    }
+    let decoder = encoding_rs_io::DecodeReaderBytesBuilder::new()
+        .encoding(encoding)
+        .bom_override(true)
+        .strip_bom(true)
+        .build(reader);
+    Ok(Box::new(BufReader::new(decoder)))
+}
+
+/// read_until(b'\n')과 같지만 limit이 있으면 줄 내용을 최대 limit + 1바이트까지만 buf에 보관하고 나머지는 버림
+/// (개행 없는 수백 MB 덩어리가 있어도 메모리가 늘지 않음). 개행은 항상 보관하므로 줄이 끝났는지는 그대로 확인 가능
+/// buf에 이미 있는 조각(이어 읽기)도 길이에 포함. 반환: 소비한 바이트 수 (0이면 EOF)
+fn read_line_limited<R: BufRead + ?Sized>(reader: &mut R, buf: &mut Vec<u8>, limit: Option<usize>) -> std::io::Result<usize> {
+    let Some(limit) = limit else {
+        return reader.read_until(b'\n', buf);
+    };
+    let keep = limit.saturating_add(1);
+    let mut consumed = 0usize;
+    loop {
+        let available = match reader.fill_buf() {
+            Ok(available) => available,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        if available.is_empty() {
+            return Ok(consumed);
+        }
+        let newline = available.iter().position(|&b| b == b'\n');
+        let content = &available[..newline.unwrap_or(available.len())];
+        let room = keep.saturating_sub(buf.len());
+        buf.extend_from_slice(&content[..content.len().min(room)]);
+        let used = content.len() + newline.is_some() as usize;
+        if newline.is_some() {
+            buf.push(b'\n');
+        }
+        reader.consume(used);
+        consumed += used;
+        if newline.is_some() {
+            return Ok(consumed);
+        }
+    }
+}
+
+/// read_line_limited로 읽은 줄이 max_line_length를 넘었는지
+fn line_too_long(buf: &[u8], limit: Option<usize>) -> bool {
+    limit.is_some_and(|limit| buf.strip_suffix(b"\n").unwrap_or(buf).len() > limit)
+}
+
+/// max_line_length를 넘은 줄의 읽기 오류 (InvalidData라 잘못된 UTF-8 줄처럼 건너뛰고 계속 읽음)
+fn line_too_long_error(limit: Option<usize>) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        format!("line exceeds max_line_length ({} bytes)", limit.unwrap_or(0)),
+    )
+}
+
+/// BufRead::lines()와 같은 줄 이터레이터 + max_line_length
+/// 한도를 넘은 줄은 line_too_long_error를 반환
+struct LimitedLines<R> {
+    reader: R,
+    limit: Option<usize>,
+}
+
+fn limited_lines<R: BufRead>(reader: R, limit: Option<usize>) -> LimitedLines<R> {
+    LimitedLines { reader, limit }
+}
+
+impl<R: BufRead> Iterator for LimitedLines<R> {
+    type Item = std::io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut buf = Vec::new();
+        match read_line_limited(&mut self.reader, &mut buf, self.limit) {
+            Ok(0) => None,
+            Ok(_) if line_too_long(&buf, self.limit) => Some(Err(line_too_long_error(self.limit))),
+            Ok(_) => {
+                if buf.last() == Some(&b'\n') {
+                    buf.pop();
+                    if buf.last() == Some(&b'\r') {
+                        buf.pop();
+                    }
+                }
+                Some(String::from_utf8(buf).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e)))
+            }
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/// 파일 함수 공통 줄 이터레이터 (limited_lines + 오류 처리)
+/// - 잘못된 UTF-8 줄, max_line_length를 넘은 줄(InvalidData)은 건너뛰고 (줄 번호, 오류)를 기록
+/// - 더 읽을 수 없는 오류(잘린 gzip 등)에서는 기록하고 멈춤 (lines()는 그런 오류를 끝없이 반복해 무한 루프가 됨)
+///
+/// GIL 없이 돌려도 되도록 오류는 모아 두었다가 반복이 끝난 뒤 report로 보고
+struct ReadLines<R> {
+    lines: LimitedLines<R>,
+    /// 마지막으로 읽은 물리적 줄 번호 (1부터, 건너뛴 줄 포함)
+    line_number: usize,
+    skipped: Vec<(usize, std::io::Error)>,
+    fatal: Option<(usize, std::io::Error)>,
+}
+
+fn read_lines<R: BufRead>(reader: R, max_line_length: Option<usize>) -> ReadLines<R> {
+    ReadLines {
+        lines: limited_lines(reader, max_line_length),
+        line_number: 0,
+        skipped: Vec::new(),
+        fatal: None,
+    }
+}
+
+impl<R: BufRead> ReadLines<R> {
+    /// 건너뛴 줄과 멈춘 오류를 모두 report_read_error로 보고 (on_error가 없으면 stderr)
+    fn report(self, py: Python<'_>, on_error: Option<&PyObject>) -> PyResult<()> {
+        for (line_number, error) in self.skipped.iter().chain(&self.fatal) {
+            report_read_error(py, on_error, *line_number, error)?;
+        }
+        Ok(())
+    }
+}
+
+impl<R: BufRead> Iterator for ReadLines<R> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        if self.fatal.is_some() {
+            return None;
+        }
+        loop {
+            let line = self.lines.next()?;
+            self.line_number += 1;
+            match line {
+                Ok(line) => return Some(line),
+                Err(e) if e.kind() == std::io::ErrorKind::InvalidData => self.skipped.push((self.line_number, e)),
+                Err(e) => {
+                    self.fatal = Some((self.line_number, e));
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+/// encoding 인자 (WHATWG 레이블: "utf-16le", "utf-16be", "euc-kr", "shift_jis" 등, 대소문자 무시)
+fn parse_encoding(label: &str) -> PyResult<&'static Encoding> {
+    Encoding::for_label(label.trim().as_bytes())
+        .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("unknown encoding '{}'", label)))
+}
+
+/// 일반 파일(gzip·BOM 아님)을 메모리 매핑해 바이트 전체를 f에 넘김
+/// 매핑할 수 없거나 mmap 기능이 꺼져 있으면 Ok(None) - 호출자는 BufReader 경로로 처리
+#[cfg(feature = "mmap")]
+fn with_mapped_file<R>(file_path: &str, f: impl FnOnce(&[u8]) -> R) -> PyResult<Option<R>> {
+    let file = File::open(file_path)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("Failed to open file: {}", e)))?;
+    if file.metadata()?.len() == 0 {
+        return Ok(Some(f(&[])));
+    }
+    // SAFETY: 읽기 전용으로만 매핑. 매핑 중 다른 프로세스가 파일을 잘라내면(copytruncate)
+    // 잘린 영역 접근 시 SIGBUS가 날 수 있으므로 follow 계열 함수에서는 쓰지 않음
+    let Ok(map) = (unsafe { memmap2::Mmap::map(&file) }) else {
+        return Ok(None);
+    };
+    if map.starts_with(&[0x1f, 0x8b]) || Encoding::for_bom(&map).is_some() {
+        return Ok(None);
+    }
+    Ok(Some(f(&map)))
+}
+
+#[cfg(not(feature = "mmap"))]
+fn with_mapped_file<R>(_file_path: &str, _f: impl FnOnce(&[u8]) -> R) -> PyResult<Option<R>> {
+    Ok(None)
+}
+
+/// 바이트 안의 줄 수 - 마지막 줄에 개행이 없어도 한 줄로 셈 (BufRead::lines()와 동일)
+fn count_lines_in(bytes: &[u8]) -> usize {
+    #[cfg(feature = "mmap")]
+    let newlines = bytecount::count(bytes, b'\n');
+    #[cfg(not(feature = "mmap"))]
+    let newlines = bytes.iter().filter(|&&b| b == b'\n').count();
+    newlines + bytes.last().is_some_and(|&b| b != b'\n') as usize
+}
+
+/// 파일의 총 줄 수를 빠르게 계산
+/// 일반 파일은 메모리 매핑 후 개행만 세고(mmap 기능), gzip·BOM 파일은 디코딩하며 셈
+#[pyfunction]
+fn count_file_lines(file_path: &str) -> PyResult<usize> {
+    if let Some(count) = with_mapped_file(file_path, count_lines_in)? {
+        return Ok(count);
+    }
+    Ok(count_reader_lines(open_log_reader(file_path)?))
+}
+
+/// 리더의 줄 수 - 잘못된 UTF-8 줄은 세고, 더 읽을 수 없는 오류(잘린 gzip 등)에서는 거기까지만 셈
+/// (BufRead::lines()는 그런 오류를 끝없이 반복해 반환하므로 count()만으로는 끝나지 않음)
+fn count_reader_lines(reader: impl BufRead) -> usize {
+    reader
+        .lines()
+        .take_while(|line| line.as_ref().map_or_else(|e| e.kind() == std::io::ErrorKind::InvalidData, |_| true))
+        .count()
+}
+
+/// encoding 옵션을 반영한 총 줄 수 (진행률 total용)
+fn count_lines_as(file_path: &str, encoding: Option<&'static Encoding>) -> PyResult<usize> {
+    match encoding {
+        None => count_file_lines(file_path),
+        Some(_) => Ok(count_reader_lines(open_log_reader_as(file_path, encoding)?)),
+    }
+}
+
+// parse_file_streaming(exact_total=False)에서 사용하는 샘플 크기
+const ESTIMATE_SAMPLE_BYTES: usize = 1 << 20;
+
+/// 파일 앞부분 `sample_bytes`만 읽어 평균 줄 길이로 전체 줄 수를 추정
+/// 샘플이 파일 전체를 덮으면 정확한 값(count_file_lines와 동일)을 반환
+/// gzip 파일은 추정 대신 count_file_lines로 정확히 셈
+#[pyfunction]
+#[pyo3(signature = (file_path, sample_bytes=ESTIMATE_SAMPLE_BYTES))]
+fn estimate_line_count(file_path: &str, sample_bytes: usize) -> PyResult<usize> {
+    let mut file = File::open(file_path)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("Failed to open file: {}", e)))?;
+    // 압축 파일은 파일 크기로 비례 추정할 수 없으므로 정확히 셈
+    let mut magic = [0u8; 2];
+    if file.read_exact(&mut magic).is_ok() && magic == [0x1f, 0x8b] {
+        return count_file_lines(file_path);
+    }
+    file.seek(SeekFrom::Start(0))?;
+    let file_size = file.metadata()?.len();
+
+    let mut sample = Vec::with_capacity(sample_bytes.min(file_size as usize));
+    file.take(sample_bytes.max(1) as u64).read_to_end(&mut sample)?;
+    let newlines = sample.iter().filter(|&&b| b == b'\n').count();
+
+    if sample.len() as u64 >= file_size {
+        // 마지막 줄에 개행이 없어도 한 줄로 셈 (BufRead::lines()와 동일)
+        let unterminated = sample.last().is_some_and(|&b| b != b'\n');
+        return Ok(newlines + unterminated as usize);
+    }
+    if newlines == 0 {
+        // 샘플 안에 줄바꿈이 없으면 추정 불가 - 최소 한 줄
+        return Ok(1);
+    }
+
+    let avg_line_len = sample.len() as f64 / newlines as f64;
+    Ok((file_size as f64 / avg_line_len).round() as usize)
+}
+
+/// 파일 앞부분 sample_lines개(빈 줄 제외)만 형식별로 몇 줄이 맞는지 세어 반환 (전체를 파싱하기 전 format_hint 선택용)
+/// 키: 내장 형식 이름(formats 옵션과 같음, 0인 형식 포함) + "custom"(add_custom_format) + "unmatched"
+/// 각 줄은 실제 파싱과 같은 우선순위로 처음 맞는 형식 하나에만 셈. GIL을 놓고 수행
+/// on_error(line_number, message): parse_log_file_chunk와 같음
+#[pyfunction]
+#[pyo3(signature = (file_path, sample_lines=1000, on_error=None))]
+fn detect_format(
+    py: Python<'_>,
+    file_path: &str,
+    sample_lines: usize,
+    on_error: Option<PyObject>,
+) -> PyResult<PyObject> {
+    let file = open_log_reader(file_path)?;
+    let mut lines = read_lines(file, None);
+
+    let (counts, custom, unmatched) = py.allow_threads(|| {
+        let mut counts: HashMap<&'static str, usize> = HashMap::new();
+        let (mut custom, mut unmatched, mut sampled) = (0usize, 0usize, 0usize);
+        for line in lines.by_ref() {
+            if sampled >= sample_lines {
+                break;
+            }
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            sampled += 1;
+            match LogFormats::matching_name(line) {
+                Some(name) => *counts.entry(name).or_default() += 1,
+                None if matches!(match_custom_fields(line), Ok(Some(_))) => custom += 1,
+                None => unmatched += 1,
+            }
+        }
+        (counts, custom, unmatched)
+    });
+    lines.report(py, on_error.as_ref())?;
+
+    let dict = PyDict::new_bound(py);
+    for (name, _) in LogFormats::NAMES {
+        dict.set_item(name, counts.get(name).copied().unwrap_or(0))?;
+    }
+    dict.set_item("custom", custom)?;
+    dict.set_item("unmatched", unmatched)?;
+    Ok(dict.into())
+}
+
+/// 파일에 등장하는 고유 태그를 정렬해 반환 (태그 필터 자동완성용)
+/// 파일 읽기와 파싱은 GIL을 놓고 한 번에 수행
+/// limit을 주면 고유 태그가 limit개가 되는 즉시 스캔을 멈춤 (처음 발견된 limit개)
+/// on_error(line_number, message): parse_log_file_chunk와 같음
+#[pyfunction]
+#[pyo3(signature = (file_path, limit=None, on_error=None))]
+fn list_tags(
+    py: Python<'_>,
+    file_path: &str,
+    limit: Option<usize>,
+    on_error: Option<PyObject>,
+) -> PyResult<Vec<String>> {
+    let file = open_log_reader(file_path)?;
+    let mut lines = read_lines(file, None);
+
+    let tags = py.allow_threads(|| {
+        let mut tags: BTreeSet<String> = BTreeSet::new();
+        for line in lines.by_ref() {
+            if let Some(fields) = parse_line_inner(&line) {
+                if !tags.contains(fields.tag) {
+                    tags.insert(fields.tag.to_string());
+                    if limit.is_some_and(|limit| tags.len() >= limit) {
+                        break;
+                    }
+                }
+            }
+        }
+        tags
+    });
+    lines.report(py, on_error.as_ref())?;
+
+    Ok(tags.into_iter().collect())
+}
+
+/// FNV-1a 64비트 해시 (Rust 버전·실행에 상관없이 같은 값 - 샤드 배정이 항상 동일)
+fn fnv1a_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &b| (hash ^ b as u64).wrapping_mul(0x100000001b3))
+}
+
+/// 파싱한 키(PID 또는 태그)의 해시로 원본 줄을 `<output_dir>/shard_<i>.log`에 분배
+/// 같은 프로세스(또는 태그)의 줄은 항상 같은 샤드에 모임
+///
+/// - key: "pid" | "tag"
+/// - PID가 "-"인 줄은 항상 shard 0
+/// - 파싱되지 않는 줄(스택 트레이스 연속 줄 등)은 직전 줄과 같은 샤드 (파일 처음이면 shard 0)
+/// - 기존 shard 파일은 덮어씀
+/// - 반환: 샤드별 줄 수
+/// - on_error(line_number, message): parse_log_file_chunk와 같음
+#[pyfunction]
+#[pyo3(signature = (input_path, output_dir, num_shards, key, on_error=None))]
+fn split_by_shard(
+    py: Python<'_>,
+    input_path: &str,
+    output_dir: &str,
+    num_shards: usize,
+    key: &str,
+    on_error: Option<PyObject>,
+) -> PyResult<Vec<usize>> {
+    if num_shards == 0 {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>("num_shards must be at least 1"));
+    }
+    let by_pid = match key {
+        "pid" => true,
+        "tag" => false,
+        _ => {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Unknown shard key '{}' (expected 'pid' or 'tag')",
+                key
+            )))
+        }
+    };
+
+    let file = open_log_reader(input_path)?;
+    let mut lines = read_lines(file, None);
+
+    let result = py.allow_threads(|| -> std::io::Result<Vec<usize>> {
+        std::fs::create_dir_all(output_dir)?;
+        let mut writers = (0..num_shards)
+            .map(|i| {
+                let path = Path::new(output_dir).join(format!("shard_{}.log", i));
+                File::create(path).map(BufWriter::new)
+            })
+            .collect::<std::io::Result<Vec<_>>>()?;
+        let mut counts = vec![0usize; num_shards];
+        let mut shard = 0usize;
+
+        for line in lines.by_ref() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            if let Some(fields) = parse_line_inner(&line) {
+                let key = if by_pid { fields.pid } else { fields.tag };
+                shard = if by_pid && key == "-" {
+                    0
+                } else {
+                    (fnv1a_hash(key.as_bytes()) % num_shards as u64) as usize
+                };
+            }
+
+            writeln!(writers[shard], "{}", line)?;
+            counts[shard] += 1;
+        }
+
+        for writer in writers.iter_mut() {
+            writer.flush()?;
+        }
+        Ok(counts)
+    })
+    .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("Failed to write shards: {}", e)))?;
+    lines.report(py, on_error.as_ref())?;
+    Ok(result)
+}
+
+/// NDJSON 출력용 항목 (기본 옵션의 dict와 같은 키)
+#[derive(Serialize)]
+struct JsonEntry<'a> {
+    timestamp: &'a str,
+    level: &'a str,
+    pid: &'a str,
+    tid: &'a str,
+    tag: &'a str,
+    message: &'a str,
+    display: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    uid: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    process: Option<&'a str>,
+}
+
+/// 항목 하나를 JSON 객체 한 줄로 기록 (기본 옵션과 같이 끝의 제어 문자 제거, 디스플레이 분류)
+fn write_json_entry<W: Write>(writer: &mut W, fields: &LogFields<'_>, pid_map: &HashMap<String, String>) -> std::io::Result<()> {
+    let tag = strip_trailing_control(fields.tag);
+    let message = strip_trailing_control(fields.message);
+    let display = classify_display(tag, fields.pid, message, None);
+    let entry = JsonEntry {
+        timestamp: fields.timestamp,
+        level: fields.level,
+        pid: fields.pid,
+        tid: fields.tid,
+        tag,
+        message: &normalize_carriage_returns(message),
+        display: &display,
+        uid: fields.uid,
+        process: pid_map.get(fields.pid).map(String::as_str),
+    };
+    serde_json::to_writer(&mut *writer, &entry)?;
+    writer.write_all(b"\n")
+}
+
+/// 태그 → 파일 이름에 쓸 수 있는 문자열 (영숫자, '.', '_', '-' 외에는 '_')
+/// 빈 문자열과 "."/".."처럼 점만 있는 이름은 앞에 '_'를 붙여 출력 디렉터리를 벗어나지 않게 함
+fn sanitize_file_name(tag: &str) -> String {
+    let name: String = tag
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') { c } else { '_' })
+        .collect();
+    if name.chars().all(|c| c == '.') {
+        format!("_{}", name)
+    } else {
+        name
+    }
+}
+
+// split_by_tag_to_json: 동시에 열어 두는 파일 최대 개수 (넘으면 모두 닫고 필요할 때 이어쓰기로 다시 엶)
+const MAX_OPEN_WRITERS: usize = 256;
+
+/// 파싱한 항목을 태그별 `<output_dir>/<tag>.ndjson`에 한 줄에 JSON 객체 하나씩 기록
+/// 파일 읽기·파싱·쓰기 모두 GIL 없이 수행하며 결과를 메모리에 모으지 않음
+///
+/// - tags: 지정하면 그 태그(정확히 일치)만 기록
+/// - 태그 이름은 파일 이름으로 쓸 수 있게 정리 (sanitize_file_name), 정리 후 같아진 태그는 한 파일에 모임
+/// - 대소문자를 구분하지 않는 파일 시스템(Windows, macOS)에 맞춰 대소문자만 다른 태그(WiFi/wifi)도 한 파일에 모음
+///   (파일 이름은 처음 나온 태그 기준)
+/// - 기존 파일은 덮어씀, 반환: 태그별 기록한 항목 수
+/// - on_error(line_number, message): parse_log_file_chunk와 같음
+#[pyfunction]
+#[pyo3(signature = (input_path, output_dir, tags=None, on_error=None))]
+fn split_by_tag_to_json(
+    py: Python<'_>,
+    input_path: &str,
+    output_dir: &str,
+    tags: Option<Vec<String>>,
+    on_error: Option<PyObject>,
+) -> PyResult<HashMap<String, usize>> {
+    let file = open_log_reader(input_path)?;
+    let mut lines = read_lines(file, None);
+    let tags: Option<HashSet<String>> = tags.map(|tags| tags.into_iter().collect());
+
+    let result = py.allow_threads(|| -> std::io::Result<HashMap<String, usize>> {
+        std::fs::create_dir_all(output_dir)?;
+        let pid_map = PID_MAP.read().unwrap();
+        // 키는 소문자로 바꾼 파일 이름, created 값은 처음 만든 실제 파일 이름
+        let mut writers: HashMap<String, BufWriter<File>> = HashMap::new();
+        let mut created: HashMap<String, String> = HashMap::new();
+        let mut counts: HashMap<String, usize> = HashMap::new();
+
+        for line in lines.by_ref() {
+            let Some(fields) = parse_line_inner(&line) else {
+                continue;
+            };
+            let tag = strip_trailing_control(fields.tag);
+            if tags.as_ref().is_some_and(|tags| !tags.contains(tag)) {
+                continue;
+            }
+
+            let file_name = sanitize_file_name(tag);
+            let key = file_name.to_lowercase();
+            if !writers.contains_key(&key) {
+                if writers.len() >= MAX_OPEN_WRITERS {
+                    for writer in writers.values_mut() {
+                        writer.flush()?;
+                    }
+                    writers.clear();
+                }
+                let file = match created.get(&key) {
+                    Some(existing) => std::fs::OpenOptions::new()
+                        .append(true)
+                        .open(Path::new(output_dir).join(format!("{}.ndjson", existing)))?,
+                    None => {
+                        let file = File::create(Path::new(output_dir).join(format!("{}.ndjson", file_name)))?;
+                        created.insert(key.clone(), file_name);
+                        file
+                    }
+                };
+                writers.insert(key.clone(), BufWriter::new(file));
+            }
+            write_json_entry(writers.get_mut(&key).unwrap(), &fields, &pid_map)?;
+            *counts.entry(tag.to_string()).or_insert(0) += 1;
+        }
+
+        for writer in writers.values_mut() {
+            writer.flush()?;
+        }
+        Ok(counts)
+    })
+    .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("Failed to write tag files: {}", e)))?;
+    lines.report(py, on_error.as_ref())?;
+    Ok(result)
+}
+
+/// 파싱한 항목을 JSON Lines(한 줄에 JSON 객체 하나)로 기록 (Elasticsearch 등 수집 파이프라인용)
+/// 형식은 split_by_tag_to_json과 같고, 제어 문자는 serde_json이 \u00XX로 이스케이프
+/// 파일 읽기·파싱·쓰기 모두 GIL 없이 수행하며 결과를 메모리에 모으지 않음
+/// 기존 파일은 덮어씀, 반환: 기록한 항목 수
+/// on_error(line_number, message): parse_log_file_chunk와 같음
+#[pyfunction]
+#[pyo3(signature = (input_path, output_path, on_error=None))]
+fn parse_file_to_jsonl(
+    py: Python<'_>,
+    input_path: &str,
+    output_path: &str,
+    on_error: Option<PyObject>,
+) -> PyResult<usize> {
+    let file = open_log_reader(input_path)?;
+    let mut lines = read_lines(file, None);
+
+    let result = py.allow_threads(|| -> std::io::Result<usize> {
+        let pid_map = PID_MAP.read().unwrap();
+        let mut writer = BufWriter::new(File::create(output_path)?);
+        let mut written = 0usize;
+        for line in lines.by_ref() {
+            if let Some(fields) = parse_line_inner(&line) {
+                write_json_entry(&mut writer, &fields, &pid_map)?;
+                written += 1;
+            }
+        }
+        writer.flush()?;
+        Ok(written)
+    })
+    .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("Failed to write JSON Lines: {}", e)))?;
+    lines.report(py, on_error.as_ref())?;
+    Ok(result)
+}
+
+// parse_file_to_csv 헤더 (열 순서)
+const CSV_HEADER: [&str; 7] = ["timestamp", "level", "pid", "tid", "tag", "message", "display"];
+
+/// 파싱한 항목을 CSV로 기록 (헤더 1줄 + 항목당 1행), 스프레드시트로 바로 열기용
+/// 파일 읽기·파싱·쓰기 모두 GIL 없이 수행하며 결과를 메모리에 모으지 않음
+/// 구분자·줄바꿈·따옴표가 들어 있는 필드는 따옴표로 감싸고 따옴표는 두 번 씀 (RFC 4180)
+///
+/// - delimiter: ASCII 한 글자 (기본 ","), 아니면 ValueError
+/// - 기존 파일은 덮어씀, 반환: 기록한 행 수 (헤더 제외)
+/// - on_error(line_number, message): parse_log_file_chunk와 같음
+#[pyfunction]
+#[pyo3(signature = (input_path, output_path, delimiter=",", on_error=None))]
+fn parse_file_to_csv(
+    py: Python<'_>,
+    input_path: &str,
+    output_path: &str,
+    delimiter: &str,
+    on_error: Option<PyObject>,
+) -> PyResult<usize> {
+    let &[delimiter] = delimiter.as_bytes() else {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "delimiter must be a single ASCII character, got '{}'",
+            delimiter
+        )));
+    };
+    let file = open_log_reader(input_path)?;
+    let mut lines = read_lines(file, None);
+
+    let result = py.allow_threads(|| -> csv::Result<usize> {
+        let mut writer = csv::WriterBuilder::new()
+            .delimiter(delimiter)
+            .from_writer(BufWriter::new(File::create(output_path)?));
+        writer.write_record(CSV_HEADER)?;
+
+        let mut rows = 0usize;
+        for line in lines.by_ref() {
+            let Some(fields) = parse_line_inner(&line) else {
+                continue;
+            };
+            let tag = strip_trailing_control(fields.tag);
+            let message = strip_trailing_control(fields.message);
+            let display = classify_display(tag, fields.pid, message, None);
+            let message = normalize_carriage_returns(message);
+            writer.write_record([fields.timestamp, fields.level, fields.pid, fields.tid, tag, &message, &display])?;
+            rows += 1;
+        }
+        writer.flush()?;
+        Ok(rows)
+    })
+    .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("Failed to write CSV: {}", e)))?;
+    lines.report(py, on_error.as_ref())?;
+    Ok(result)
+}
+
+/// 파싱한 항목을 Unix 도메인 소켓에 NDJSON으로 전송 (로그 분석 데몬과의 생산자/소비자 분리용)
+/// 소켓은 이미 listen 중이어야 함. 쓰기는 블로킹이라 받는 쪽이 느리면 그만큼 기다림 (백프레셔)
+/// 전송 형식은 split_by_tag_to_json과 같음, 반환: 보낸 항목 수
+/// on_error(line_number, message): parse_log_file_chunk와 같음
+#[cfg(unix)]
+#[pyfunction]
+#[pyo3(signature = (input_path, socket_path, on_error=None))]
+fn parse_file_to_socket(
+    py: Python<'_>,
+    input_path: &str,
+    socket_path: &str,
+    on_error: Option<PyObject>,
+) -> PyResult<usize> {
+    let file = open_log_reader(input_path)?;
+    let mut lines = read_lines(file, None);
+    let stream = std::os::unix::net::UnixStream::connect(socket_path)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyConnectionError, _>(format!("Failed to connect socket: {}", e)))?;
+
+    let result = py.allow_threads(|| -> std::io::Result<usize> {
+        let pid_map = PID_MAP.read().unwrap();
+        let mut writer = BufWriter::new(stream);
+        let mut sent = 0usize;
+        for line in lines.by_ref() {
+            if let Some(fields) = parse_line_inner(&line) {
+                write_json_entry(&mut writer, &fields, &pid_map)?;
+                sent += 1;
+            }
+        }
+        writer.flush()?;
+        Ok(sent)
+    })
+    .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("Failed to write to socket: {}", e)))?;
+    lines.report(py, on_error.as_ref())?;
+    Ok(result)
+}
+
+#[cfg(not(unix))]
+#[pyfunction]
+#[pyo3(signature = (_input_path, _socket_path, _on_error=None))]
+fn parse_file_to_socket(_input_path: &str, _socket_path: &str, _on_error: Option<PyObject>) -> PyResult<usize> {
+    Err(PyErr::new::<pyo3::exceptions::PyNotImplementedError, _>(
+        "parse_file_to_socket requires Unix domain sockets",
+    ))
+}
+
+/// UID별로 항목을 묶어 반환 (-v uid 캡처의 드라이버/동승자 사용자 프로필 분석용)
+/// 파일 순서대로 한 ParseState로 파싱한 뒤 (merge_*, display_sticky 등이 UID 그룹 사이에 섞이지 않음)
+/// 완성된 항목을 결과의 uid 값으로 묶음. uid가 없는 항목(다른 형식, keep_unparsed 항목)은 "unknown"
+/// on_error(line_number, message): parse_log_file_chunk와 같음
+#[pyfunction]
+#[pyo3(signature = (file_path, on_error=None, **options))]
+fn group_by_uid(
+    py: Python<'_>,
+    file_path: &str,
+    on_error: Option<PyObject>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<HashMap<String, Vec<PyObject>>> {
+    let options = ParseOptions::from_kwargs(options)?;
+    let file = open_log_reader_as(file_path, options.encoding)?;
+    let mut lines = read_lines(file, options.max_line_length);
+
+    let mut state = ParseState::default();
+    let mut groups: HashMap<String, Vec<PyObject>> = HashMap::new();
+    for line in lines.by_ref() {
+        let entry = parse_entry(py, &line, &options, &mut state);
+        state.take_error()?;
+        let Some(entry) = entry else {
+            continue;
+        };
+        // 뒤의 연속 줄이 합쳐져도 같은 dict이므로 지금 묶어도 됨
+        let uid = entry
+            .bind(py)
+            .downcast::<PyDict>()
+            .ok()
+            .and_then(|dict| dict.get_item("uid").ok().flatten())
+            .and_then(|uid| uid.extract::<String>().ok())
+            .unwrap_or_else(|| "unknown".to_string());
+        groups.entry(uid).or_default().push(entry);
+    }
+    lines.report(py, on_error.as_ref())?;
+    Ok(groups)
+}
+
+/// 태그마다 처음 파싱된 항목 하나씩 반환 (어떤 컴포넌트가 활동하고 처음 무엇을 남기는지 개요용)
+/// 이미 본 태그는 HashSet으로 바로 건너뛰고, 스캔은 GIL 없이 한 뒤 첫 항목만 dict로 만듦
+/// 반환 순서는 태그가 처음 등장한 순서
+/// formats와 필터(min_level, filter_tag 등)를 통과한 줄만 그 태그의 첫 항목으로 셈
+/// on_error(line_number, message): parse_log_file_chunk와 같음
+#[pyfunction]
+#[pyo3(signature = (file_path, on_error=None, **options))]
+fn first_per_tag(
+    py: Python<'_>,
+    file_path: &str,
+    on_error: Option<PyObject>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Vec<PyObject>> {
+    let options = ParseOptions::from_kwargs(options)?;
+    let file = open_log_reader_as(file_path, options.encoding)?;
+    let mut lines = read_lines(file, options.max_line_length);
+
+    let first_lines = py.allow_threads(|| {
+        let mut seen: HashSet<String> = HashSet::new();
+        let mut first_lines: Vec<String> = Vec::new();
+        for line in lines.by_ref() {
+            let Ok(Some(mut fields)) = match_entry_fields(&line, options.formats) else {
+                continue;
+            };
+            if !options.keep_control {
+                fields.tag = strip_trailing_control(fields.tag);
+                fields.message = strip_trailing_control(fields.message);
+            }
+            if options.filters.matched(&fields).is_none() {
+                continue;
+            }
+            if !seen.contains(fields.tag) {
+                seen.insert(fields.tag.to_string());
+                first_lines.push(line);
+            }
+        }
+        first_lines
+    });
+    lines.report(py, on_error.as_ref())?;
+
+    let mut state = ParseState::default();
+    let entries = first_lines
+        .iter()
+        .filter_map(|line| parse_entry(py, line, &options, &mut state))
+        .collect();
+    state.take_error()?;
+    Ok(entries)
+}
+
+/// 버그리포트의 dumpsys/dumpstate 섹션 (index_dumpsys_sections)
+struct DumpsysSection {
+    name: String,
+    /// 헤더의 괄호 안 명령 (예: "/proc/meminfo"), 없으면 None
+    command: Option<String>,
+    /// 0: dumpstate 섹션, 1: 그 안의 "DUMP OF SERVICE" 서비스
+    depth: u8,
+    start_line: usize,
+    end_line: usize,
+}
+
+/// 열린 섹션(stack)을 depth 이상부터 닫음
+fn close_sections(stack: &mut Vec<DumpsysSection>, done: &mut Vec<DumpsysSection>, depth: u8, end_line: usize) {
+    while stack.last().is_some_and(|section| section.depth >= depth) {
+        let mut section = stack.pop().unwrap();
+        section.end_line = end_line.max(section.start_line);
+        done.push(section);
+    }
+}
+
+/// 버그리포트(dumpstate 출력)의 dumpsys 섹션 색인 - 도구에서 meminfo, activity 같은 섹션으로 바로 이동하기 위함
+/// 반환: [{"section_name", "command", "depth", "start_line", "end_line"}, ...] 시작 줄 순 (줄 번호는 1부터, 끝 줄 포함)
+///
+/// - `------ NAME (command) ------` 헤더가 depth 0 섹션을 시작 (앞 섹션은 그 전 줄에서 닫힘)
+/// - 그 안의 `DUMP OF SERVICE name:`은 depth 1 서비스 섹션
+/// - `... was the duration of 'NAME'` / `... duration of dumpsys name` 줄이 있으면 그 줄에서 해당 섹션을 닫음
+/// - 끝 표시가 없으면 다음 헤더 전 줄 또는 파일 끝까지
+/// 읽기 전용 스캔이며 GIL 없이 수행
+/// on_error(line_number, message): parse_log_file_chunk와 같음
+#[pyfunction]
+#[pyo3(signature = (file_path, on_error=None))]
+fn index_dumpsys_sections(py: Python<'_>, file_path: &str, on_error: Option<PyObject>) -> PyResult<Vec<PyObject>> {
+    let file = open_log_reader(file_path)?;
+    let mut lines = read_lines(file, None);
+
+    let mut sections = py.allow_threads(|| {
+        let mut stack: Vec<DumpsysSection> = Vec::new();
+        let mut done: Vec<DumpsysSection> = Vec::new();
+        let mut line_no = 0usize;
+
+        while let Some(line) = lines.next() {
+            line_no = lines.line_number;
+            let trimmed = line.trim();
+
+            if let Some(caps) = DUMPSYS_DURATION_PATTERN.captures(trimmed) {
+                let (name, depth) = match (caps.get(1), caps.get(2)) {
+                    (Some(name), _) => (name.as_str(), 0),
+                    (None, Some(name)) => (name.as_str(), 1),
+                    _ => continue,
+                };
+                // 같은 이름의 열린 섹션이 있을 때만 그 섹션(과 안쪽 섹션)을 이 줄에서 닫음
+                if stack.iter().any(|section| section.depth == depth && section.name == name) {
+                    close_sections(&mut stack, &mut done, depth, line_no);
+                }
+                continue;
+            }
+
+            if let Some(caps) = DUMPSYS_HEADER_PATTERN.captures(trimmed) {
+                let title = caps.get(1).map_or("", |m| m.as_str());
+                let (name, command) = match title.strip_suffix(')').and_then(|t| t.split_once(" (")) {
+                    Some((name, command)) => (name.trim(), Some(command.to_string())),
+                    None => (title, None),
+                };
+                close_sections(&mut stack, &mut done, 0, line_no - 1);
+                stack.push(DumpsysSection {
+                    name: name.to_string(),
+                    command,
+                    depth: 0,
+                    start_line: line_no,
+                    end_line: line_no,
+                });
+                continue;
+            }
+
+            if let Some(caps) = DUMP_OF_SERVICE_PATTERN.captures(trimmed) {
+                close_sections(&mut stack, &mut done, 1, line_no - 1);
+                stack.push(DumpsysSection {
+                    name: caps.get(1).map_or("", |m| m.as_str()).to_string(),
+                    command: None,
+                    depth: 1,
+                    start_line: line_no,
+                    end_line: line_no,
+                });
+            }
+        }
+        close_sections(&mut stack, &mut done, 0, line_no);
+        done
+    });
+    lines.report(py, on_error.as_ref())?;
+
+    sections.sort_by_key(|section| (section.start_line, section.depth));
+    sections
+        .into_iter()
+        .map(|section| {
+            let dict = PyDict::new_bound(py);
+            dict.set_item("section_name", section.name)?;
+            dict.set_item("command", section.command)?;
+            dict.set_item("depth", section.depth)?;
+            dict.set_item("start_line", section.start_line)?;
+            dict.set_item("end_line", section.end_line)?;
+            Ok(dict.into())
+        })
+        .collect()
+}
+
+/// 같은 타임스탬프가 연속된 구간 (find_timestamp_clusters)
+struct TimestampCluster {
+    timestamp: String,
+    count: usize,
+    /// 구간 첫 항목의 줄 번호 (1부터)
+    start_line: usize,
+}
+
+/// 타임스탬프가 완전히 같은 항목이 min_cluster개 이상 연속된 구간 (시계 정지, 일괄 flush 등 캡처 왜곡 탐지)
+/// 파싱되지 않는 줄은 항목이 아니므로 구간을 끊지 않음. 스캔은 GIL 없이 한 번만 수행
+/// 반환: [{"timestamp", "count", "start_line"}, ...] 파일 순서
+/// on_error(line_number, message): parse_log_file_chunk와 같음
+#[pyfunction]
+#[pyo3(signature = (file_path, min_cluster, on_error=None))]
+fn find_timestamp_clusters(
+    py: Python<'_>,
+    file_path: &str,
+    min_cluster: usize,
+    on_error: Option<PyObject>,
+) -> PyResult<Vec<PyObject>> {
+    let file = open_log_reader(file_path)?;
+    let mut lines = read_lines(file, None);
+
+    let clusters = py.allow_threads(|| {
+        let mut clusters: Vec<TimestampCluster> = Vec::new();
+        let mut current: Option<TimestampCluster> = None;
+        let mut push_if_large = |cluster: TimestampCluster| {
+            if cluster.count >= min_cluster {
+                clusters.push(cluster);
+            }
+        };
+
+        while let Some(line) = lines.next() {
+            let Some(fields) = parse_line_inner(&line) else {
+                continue;
+            };
+
+            match current.as_mut() {
+                Some(cluster) if cluster.timestamp == fields.timestamp => cluster.count += 1,
+                _ => {
+                    let next = TimestampCluster {
+                        timestamp: fields.timestamp.to_string(),
+                        count: 1,
+                        start_line: lines.line_number,
+                    };
+                    if let Some(finished) = current.replace(next) {
+                        push_if_large(finished);
+                    }
+                }
+            }
+        }
+        if let Some(finished) = current {
+            push_if_large(finished);
+        }
+        clusters
+    });
+    lines.report(py, on_error.as_ref())?;
+
+    clusters
+        .into_iter()
+        .map(|cluster| {
+            let dict = PyDict::new_bound(py);
+            dict.set_item("timestamp", cluster.timestamp)?;
+            dict.set_item("count", cluster.count)?;
+            dict.set_item("start_line", cluster.start_line)?;
+            Ok(dict.into())
+        })
+        .collect()
+}
+
+/// 장애 지점(detect_events와 같은 감지)만 골라 위치를 반환 - 긴 캡처에서 사고 지점으로 바로 이동용
+/// 스캔은 GIL 없이 한 번만 수행하며 항목 dict를 만들지 않음
+/// 반환: [{"line", "timestamp", "event_type", "tag"}, ...] 파일 순서, line은 물리적 줄 번호 (1부터)
+/// on_error(line_number, message): parse_log_file_chunk와 같음
+#[pyfunction]
+#[pyo3(signature = (file_path, on_error=None))]
+fn find_events(py: Python<'_>, file_path: &str, on_error: Option<PyObject>) -> PyResult<Vec<PyObject>> {
+    let file = open_log_reader(file_path)?;
+    let mut lines = read_lines(file, None);
+
+    let events = py.allow_threads(|| {
+        let mut events: Vec<(usize, String, &'static str, String)> = Vec::new();
+        while let Some(line) = lines.next() {
+            let Some(fields) = parse_line_inner(&line) else {
+                continue;
+            };
+            let tag = strip_trailing_control(fields.tag);
+            if let Some(event_type) = detect_failure_event(tag, strip_trailing_control(fields.message)) {
+                events.push((lines.line_number, fields.timestamp.to_string(), event_type, tag.to_string()));
+            }
+        }
+        events
+    });
+    lines.report(py, on_error.as_ref())?;
+
+    events
+        .into_iter()
+        .map(|(line, timestamp, event_type, tag)| {
+            let dict = PyDict::new_bound(py);
+            dict.set_item("line", line)?;
+            dict.set_item("timestamp", timestamp)?;
+            dict.set_item("event_type", event_type)?;
+            dict.set_item("tag", tag)?;
+            Ok(dict.into())
+        })
+        .collect()
+}
+
+/// 태그 전이 행렬: 상위 top_n개 태그에 대해 태그 A 바로 다음에 태그 B가 나온 횟수
+/// 이상 동작 탐지용 태그 시퀀스 모델. 스트리밍 한 번에 중첩 HashMap으로 모든 전이를 센 뒤 상위 태그만 남김
+/// 반환: {A: {B: count}} - 상위 태그의 모든 쌍을 포함(0 포함), 빈도 내림차순(같으면 이름순)
+/// 파싱되지 않는 줄은 건너뛰므로 전이는 파싱된 항목 사이에서만 셈
+/// on_error(line_number, message): parse_log_file_chunk와 같음
+#[pyfunction]
+#[pyo3(signature = (file_path, top_n, on_error=None))]
+fn tag_transition_matrix(
+    py: Python<'_>,
+    file_path: &str,
+    top_n: usize,
+    on_error: Option<PyObject>,
+) -> PyResult<PyObject> {
+    let file = open_log_reader(file_path)?;
+    let mut lines = read_lines(file, None);
+
+    let (top_tags, transitions) = py.allow_threads(|| {
+        let mut tag_counts: HashMap<String, usize> = HashMap::new();
+        let mut transitions: HashMap<String, HashMap<String, usize>> = HashMap::new();
+        let mut previous: Option<String> = None;
+
+        for line in lines.by_ref() {
+            let Some(fields) = parse_line_inner(&line) else {
+                continue;
+            };
+            let tag = strip_trailing_control(fields.tag);
+            *tag_counts.entry(tag.to_string()).or_insert(0) += 1;
+            if let Some(previous) = &previous {
+                let row = transitions.entry(previous.clone()).or_default();
+                *row.entry(tag.to_string()).or_insert(0) += 1;
+            }
+            if previous.as_deref() != Some(tag) {
+                previous = Some(tag.to_string());
+            }
+        }
+
+        let mut ranked: Vec<(String, usize)> = tag_counts.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        let top_tags: Vec<String> = ranked.into_iter().take(top_n).map(|(tag, _)| tag).collect();
+        (top_tags, transitions)
+    });
+    lines.report(py, on_error.as_ref())?;
+
+    let matrix = PyDict::new_bound(py);
+    for from in &top_tags {
+        let row = PyDict::new_bound(py);
+        let counts = transitions.get(from);
+        for to in &top_tags {
+            let count = counts.and_then(|counts| counts.get(to)).copied().unwrap_or(0);
+            row.set_item(to, count)?;
+        }
+        matrix.set_item(from, row)?;
+    }
+    Ok(matrix.into())
+}
+
+// compare_coverage: 한쪽에서만 파싱된 줄 샘플 최대 개수
+const COVERAGE_SAMPLE_LIMIT: usize = 20;
+
+/// 두 옵션 구성의 파싱 커버리지 비교 (옵션·형식 튜닝용)
+/// 파일을 한 번만 읽으며 줄마다 두 구성을 모두 적용
+/// 반환: {"total_lines", "a_parsed", "b_parsed", "a_only", "b_only", "a_only_lines", "b_only_lines"}
+/// - *_parsed: 항목이 만들어진 줄 수 (merge_* 옵션으로 앞 항목에 합쳐진 줄은 제외)
+/// - *_only_lines: 그쪽 구성에서만 파싱된 원본 줄 샘플 (최대 20개)
+/// - on_error(line_number, message): parse_log_file_chunk와 같음
+#[pyfunction]
+#[pyo3(signature = (file_path, options_a=None, options_b=None, on_error=None))]
+fn compare_coverage(
+    py: Python<'_>,
+    file_path: &str,
+    options_a: Option<&Bound<'_, PyDict>>,
+    options_b: Option<&Bound<'_, PyDict>>,
+    on_error: Option<PyObject>,
+) -> PyResult<PyObject> {
+    let mut options_a = ParseOptions::from_kwargs(options_a)?;
+    let mut options_b = ParseOptions::from_kwargs(options_b)?;
+    // 파싱 실패 줄까지 항목으로 만들면 비교가 무의미하므로 keep_unparsed는 무시
+    options_a.keep_unparsed = false;
+    options_b.keep_unparsed = false;
+    let file = open_log_reader_as(file_path, options_a.encoding.or(options_b.encoding))?;
+
+    let mut state_a = ParseState::default();
+    let mut state_b = ParseState::default();
+    let (mut total_lines, mut a_parsed, mut b_parsed, mut a_only, mut b_only) = (0usize, 0usize, 0usize, 0usize, 0usize);
+    let mut a_only_lines: Vec<String> = Vec::new();
+    let mut b_only_lines: Vec<String> = Vec::new();
+
+    // 사용자 형식 매칭 시간 초과 항목(regex_timeout)은 keep_unparsed와 무관하게 나오므로 파싱 실패로 셈
+    let covered = |entry: Option<PyObject>| {
+        entry.is_some_and(|entry| {
+            entry
+                .bind(py)
+                .downcast::<PyDict>()
+                .map_or(true, |dict| !dict.contains("regex_timeout").unwrap_or(false))
+        })
+    };
+
+    let mut lines = read_lines(file, None);
+    for line in lines.by_ref() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        total_lines += 1;
+
+        let in_a = covered(parse_entry(py, trimmed, &options_a, &mut state_a));
+        let in_b = covered(parse_entry(py, trimmed, &options_b, &mut state_b));
+        state_a.take_error()?;
+        state_b.take_error()?;
+        a_parsed += in_a as usize;
+        b_parsed += in_b as usize;
+        match (in_a, in_b) {
+            (true, false) => {
+                a_only += 1;
+                if a_only_lines.len() < COVERAGE_SAMPLE_LIMIT {
+                    a_only_lines.push(trimmed.to_string());
+                }
+            }
+            (false, true) => {
+                b_only += 1;
+                if b_only_lines.len() < COVERAGE_SAMPLE_LIMIT {
+                    b_only_lines.push(trimmed.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    lines.report(py, on_error.as_ref())?;
+
+    let report = PyDict::new_bound(py);
+    report.set_item("total_lines", total_lines)?;
+    report.set_item("a_parsed", a_parsed)?;
+    report.set_item("b_parsed", b_parsed)?;
+    report.set_item("a_only", a_only)?;
+    report.set_item("b_only", b_only)?;
+    report.set_item("a_only_lines", a_only_lines)?;
+    report.set_item("b_only_lines", b_only_lines)?;
+    Ok(report.into())
+}
+
+/// 심각도 구간 (level_segments)
+struct LevelSegment {
+    start_offset: u64,
+    end_offset: u64,
+    dominant_level: String,
+    count: usize,
+}
+
+/// 블록 안에서 가장 많은 레벨 (같으면 더 심각한 레벨)
+fn dominant_level(counts: &HashMap<String, usize>) -> Option<String> {
+    counts
+        .iter()
+        .max_by_key(|(level, count)| (**count, level_num(level)))
+        .map(|(level, _)| level.clone())
+}
+
+/// 파일 전체를 심각도 구간으로 나눠 바이트 범위 반환 (뷰어 스크롤바 히트맵용)
+///
+/// 구간 나누기 방식:
+/// 1. 파일을 `block_lines`줄 단위 블록으로 나눔 (파싱 안 되는 줄도 블록에 포함되어 범위가 끊기지 않음)
+/// 2. 블록마다 파싱된 줄의 레벨 중 가장 많은 레벨을 대표 레벨로 정함 (같으면 더 심각한 레벨)
+/// 3. 대표 레벨이 같은 인접 블록을 하나의 구간으로 합침
+///
+/// 블록이 작을수록 짧은 E 버스트도 잡히지만 구간 수가 늘어남
+/// 반환: [{start_offset, end_offset(미포함), dominant_level, count(파싱된 줄 수)}, ...]
+/// gzip·BOM 파일은 압축을 풀고 UTF-8로 바꾼 스트림 기준 오프셋 (원본 파일에 seek할 수 있는 값은 아님)
+/// 전체 항목을 메모리에 들고 있지 않고 한 번만 읽으며 GIL 없이 수행
+#[pyfunction]
+#[pyo3(signature = (file_path, block_lines=100))]
+fn level_segments(py: Python<'_>, file_path: &str, block_lines: usize) -> PyResult<Vec<PyObject>> {
+    let mut reader = open_log_reader(file_path)?;
+    let block_lines = block_lines.max(1);
+
+    let (segments, read_error) = py.allow_threads(|| {
+        let mut segments: Vec<LevelSegment> = Vec::new();
+        let mut buf: Vec<u8> = Vec::new();
+        let mut offset = 0u64;
+        let mut block_start = 0u64;
+        let mut block_line_count = 0usize;
+        let mut block_counts: HashMap<String, usize> = HashMap::new();
+        let mut line_number = 0usize;
+        let mut read_error = None;
+
+        let mut close_block = |block_start: u64, block_end: u64, counts: &mut HashMap<String, usize>| {
+            let parsed: usize = counts.values().sum();
+            if let Some(level) = dominant_level(counts) {
+                match segments.last_mut() {
+                    Some(last) if last.dominant_level == level => {
+                        last.end_offset = block_end;
+                        last.count += parsed;
+                    }
+                    _ => segments.push(LevelSegment {
+                        start_offset: block_start,
+                        end_offset: block_end,
+                        dominant_level: level,
+                        count: parsed,
+                    }),
+                }
+            } else if let Some(last) = segments.last_mut() {
+                // 파싱된 줄이 없는 블록은 앞 구간에 붙임
+                last.end_offset = block_end;
+            }
+            counts.clear();
+        };
+
+        loop {
+            buf.clear();
+            let n = match reader.read_until(b'\n', &mut buf) {
+                Ok(0) => break,
+                Ok(n) => n,
+                Err(e) => {
+                    read_error = Some((line_number + 1, e));
+                    break;
+                }
+            };
+            offset += n as u64;
+            line_number += 1;
+
+            let line = String::from_utf8_lossy(&buf);
+            if let Some(fields) = parse_line_inner(&line) {
+                *block_counts.entry(fields.level.to_string()).or_default() += 1;
+            }
+            block_line_count += 1;
+
+            if block_line_count >= block_lines {
+                close_block(block_start, offset, &mut block_counts);
+                block_start = offset;
+                block_line_count = 0;
+            }
+        }
+        if block_line_count > 0 {
+            close_block(block_start, offset, &mut block_counts);
+        }
+        (segments, read_error)
+    });
+    if let Some((line_number, error)) = read_error {
+        return Err(PyErr::new::<pyo3::exceptions::PyIOError, _>(format!(
+            "Failed to read file at line {}: {}",
+            line_number, error
+        )));
+    }
+
+    segments
+        .into_iter()
+        .map(|segment| {
+            let dict = PyDict::new_bound(py);
+            dict.set_item("start_offset", segment.start_offset)?;
+            dict.set_item("end_offset", segment.end_offset)?;
+            dict.set_item("dominant_level", segment.dominant_level)?;
+            dict.set_item("count", segment.count)?;
+            Ok(dict.into())
+        })
+        .collect()
+}
+
+/// 시간 창 단위로 묶어 콜백 호출 (슬라이딩 윈도우 분석용)
+/// callback(entries: List[Dict], window_start_ms: int, window_end_ms: int) -> bool
+///
+/// - 창은 [start, start + window_ms) 이고 step_ms마다 이동 (step_ms < window_ms면 겹치며, 겹친 항목은 양쪽 창에 모두 포함)
+/// - 첫 창은 첫 항목의 시각에서 시작, 시각은 timestamp_to_millis 기준 (연도 1월 1일 0시 기준 ms)
+/// - 타임스탬프를 해석할 수 없거나 시간이 거꾸로 가는 항목은 직전 시각으로 취급해 현재 창에 붙임
+/// - 항목이 하나도 없는 창은 건너뜀
+/// - 줄은 parse_entry로 파싱 (formats, merge_*, collapse_duplicates, keep_unparsed, sample_rate 등 적용)
+/// - 겹친 창이 같은 dict를 공유하므로 columnar, as_objects와 전체 결과가 필요한 max_rows는 ValueError
+/// - on_error(line_number, message): parse_log_file_chunk와 같음 (마지막 창 뒤에 호출)
+/// - 반환: 콜백을 호출한 창 개수
+#[pyfunction]
+#[pyo3(signature = (file_path, window_ms, step_ms, callback, on_error=None, **options))]
+fn parse_windows(
+    py: Python<'_>,
+    file_path: &str,
+    window_ms: u64,
+    step_ms: u64,
+    callback: PyObject,
+    on_error: Option<PyObject>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<usize> {
+    let options = ParseOptions::from_kwargs(options)?;
+    if window_ms == 0 || step_ms == 0 {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>("window_ms and step_ms must be positive"));
+    }
+    if options.columnar || options.as_objects || options.max_rows.is_some() {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "parse_windows does not support columnar, as_objects or max_rows",
+        ));
+    }
+    let (window_ms, step_ms) = (window_ms as i64, step_ms as i64);
+
+    let file = open_log_reader_as(file_path, options.encoding)?;
+
+    let mut state = ParseState::default();
+    // 현재 창 시작 이후의 항목들 (시각 오름차순)
+    let mut pending: VecDeque<(i64, PyObject)> = VecDeque::new();
+    let mut window_start: Option<i64> = None;
+    let mut last_ts = i64::MIN;
+    let mut windows = 0usize;
+    let mut sampled = 0usize;
+
+    // 현재 창을 콜백으로 넘기고 다음 창으로 이동. 콜백이 계속을 원하면 true
+    let mut emit = |pending: &mut VecDeque<(i64, PyObject)>, start: &mut i64| -> bool {
+        let end = *start + window_ms;
+        let entries: Vec<PyObject> = pending
+            .iter()
+            .take_while(|(ts, _)| *ts < end)
+            .map(|(_, entry)| entry.clone_ref(py))
+            .collect();
+
+        let mut should_continue = true;
+        if !entries.is_empty() {
+            windows += 1;
+            should_continue = match callback.call1(py, (entries, *start, end)) {
+                Ok(obj) => obj.extract::<bool>(py).unwrap_or(true),
+                Err(_) => false, // 에러 시 중단
+            };
+        }
+
+        *start += step_ms;
+        while pending.front().is_some_and(|(ts, _)| *ts < *start) {
+            pending.pop_front();
+        }
+        // 긴 공백 구간은 빈 창을 하나씩 넘기지 않고 다음 항목이 들어가는 창으로 바로 이동
+        if let Some((next_ts, _)) = pending.front() {
+            if *next_ts >= *start + window_ms {
+                *start += (*next_ts - *start - window_ms) / step_ms * step_ms + step_ms;
+            }
+        }
+        should_continue
+    };
+
+    let mut lines = read_lines(file, options.max_line_length);
+    let mut stopped = false;
+    'lines: for line in lines.by_ref() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let entry = parse_entry(py, trimmed, &options, &mut state);
+        state.take_error()?;
+        let Some(entry) = entry else {
+            continue;
+        };
+        let Some(entry) = sample_every(vec![entry], options.sample_rate, &mut sampled).pop() else {
+            continue;
+        };
+        // keep_unparsed 항목은 timestamp가 없으므로 직전 시각
+        let parsed_ts = entry
+            .bind(py)
+            .downcast::<PyDict>()
+            .ok()
+            .and_then(|dict| dict.get_item("timestamp").ok().flatten())
+            .and_then(|timestamp| timestamp.extract::<String>().ok())
+            .and_then(|timestamp| timestamp_to_millis(&timestamp));
+        if last_ts == i64::MIN {
+            last_ts = parsed_ts.unwrap_or(0);
+        }
+        let ts = parsed_ts.unwrap_or(last_ts).max(last_ts);
+        last_ts = ts;
+
+        let start = window_start.get_or_insert(ts);
+        while ts >= *start + window_ms {
+            if !emit(&mut pending, start) {
+                stopped = true;
+                break 'lines;
+            }
+            if pending.is_empty() && ts >= *start + window_ms {
+                // 창이 비었으면 새 항목이 들어가는 첫 창으로 이동
+                *start += (ts - *start - window_ms) / step_ms * step_ms + step_ms;
+            }
+        }
+        pending.push_back((ts, entry));
+    }
+
+    // 남은 항목이 모두 빠질 때까지 창 이동
+    if let (false, Some(mut start)) = (stopped, window_start) {
+        while !pending.is_empty() {
+            if !emit(&mut pending, &mut start) {
+                break;
+            }
+        }
+    }
+
+    lines.report(py, on_error.as_ref())?;
+    Ok(windows)
+}
+
+/// profile 모드에서 청크 하나를 처리하는 데 걸린 시간
+struct ChunkTiming {
+    lines: usize,
+    read_ms: f64,
+    parse_ms: f64,
+    callback_ms: f64,
+}
+
+fn elapsed_ms(start: Instant) -> f64 {
+    start.elapsed().as_secs_f64() * 1000.0
+}
+
+/// profile 결과를 Python dict로 변환
+/// {"read_ms", "parse_ms", "callback_ms"} 합계 + 청크별 "chunks" 리스트
+fn timings_to_dict(py: Python<'_>, timings: &[ChunkTiming]) -> PyResult<PyObject> {
+    let chunks = PyList::empty_bound(py);
+    let (mut read_ms, mut parse_ms, mut callback_ms) = (0.0, 0.0, 0.0);
+    for timing in timings {
+        let chunk = PyDict::new_bound(py);
+        chunk.set_item("lines", timing.lines)?;
+        chunk.set_item("read_ms", timing.read_ms)?;
+        chunk.set_item("parse_ms", timing.parse_ms)?;
+        chunk.set_item("callback_ms", timing.callback_ms)?;
+        chunks.append(chunk)?;
+        read_ms += timing.read_ms;
+        parse_ms += timing.parse_ms;
+        callback_ms += timing.callback_ms;
+    }
+
+    let report = PyDict::new_bound(py);
+    report.set_item("read_ms", read_ms)?;
+    report.set_item("parse_ms", parse_ms)?;
+    report.set_item("callback_ms", callback_ms)?;
+    report.set_item("chunks", chunks)?;
+    Ok(report.into())
+}
+
+/// 파일을 한 번만 읽고 청크마다 콜백 호출 (O(n) - 가장 효율적)
+/// callback(parsed_logs: List[Dict], progress: int, total: int) -> bool
+/// 콜백이 False 반환하면 중단
+///
+/// profile=True면 청크별 read/parse/callback 소요 시간(ms)을 측정해
+/// (total_parsed, report)를 반환. 꺼져 있으면 시간 측정을 하지 않음
+///
+/// exact_total=False면 전체 줄 수를 세는 사전 읽기 대신 estimate_line_count로 추정
+/// (추정값보다 많이 읽으면 total은 현재 줄 수로 보정되어 진행률이 100%를 넘지 않음)
+///
+/// start_line=N이면 앞의 물리적 줄 N개(빈 줄·읽기 오류 줄 포함)를 건너뛰고 시작
+/// progress/total은 파일 전체 기준 줄 번호라 이어서 읽어도 진행률이 맞음
+///
+/// start_offset을 주면 그 바이트 위치부터 읽고 (total_parsed, end_offset)를 반환
+/// (profile이면 (total_parsed, end_offset, report)). end_offset을 다음 호출에 넘기면 이어서 읽음
+/// 오프셋이 줄 중간이면 다음 줄부터 시작하고, 개행이 없는 마지막 줄(기록 중)은 읽지 않고 남겨 둠
+/// progress/total은 오프셋 이후 줄 기준. 바이트 위치를 쓰므로 gzip·UTF-16 BOM 파일과 encoding은 ValueError
+///
+/// on_error(line_number, message): 줄 읽기 오류마다 호출 (parse_log_file_chunk와 같음, 줄 번호는 progress와 같은 기준)
+#[pyfunction]
+#[pyo3(signature = (file_path, chunk_size, callback, profile=false, exact_total=true, start_line=0, start_offset=None, on_error=None, **options))]
+#[allow(clippy::too_many_arguments)]
+fn parse_file_streaming(
+    py: Python<'_>,
+    file_path: &str,
+    chunk_size: usize,
+    callback: PyObject,
+    profile: bool,
+    exact_total: bool,
+    start_line: usize,
+    start_offset: Option<u64>,
+    on_error: Option<PyObject>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<PyObject> {
+    let options = ParseOptions::from_kwargs(options)?;
+    if start_offset.is_some() && start_line > 0 {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "start_line and start_offset cannot be combined",
+        ));
+    }
+    let (mut reader, position): (LogReader, u64) = match start_offset {
+        Some(offset) => {
+            let (reader, position) = open_at_line_boundary(file_path, offset, options.encoding)?;
+            (Box::new(reader), position)
+        }
+        None => (open_log_reader_as(file_path, options.encoding)?, 0),
+    };
+
+    // 먼저 총 줄 수 계산 (진행률용)
+    let total_lines = match (exact_total, start_offset) {
+        (true, Some(_)) => match with_mapped_file(file_path, |bytes| count_lines_in(&bytes[(position as usize).min(bytes.len())..]))? {
+            Some(count) => count,
+            None => count_reader_lines(open_at_line_boundary(file_path, position, options.encoding)?.0),
+        },
+        (true, None) => count_lines_as(file_path, options.encoding)?,
+        (false, Some(_)) => {
+            // 남은 바이트 비율만큼 추정
+            let file_size = std::fs::metadata(file_path)?.len().max(1);
+            let remaining = file_size.saturating_sub(position) as f64 / file_size as f64;
+            (estimate_line_count(file_path, ESTIMATE_SAMPLE_BYTES)? as f64 * remaining).round() as usize
+        }
+        (false, None) => estimate_line_count(file_path, ESTIMATE_SAMPLE_BYTES)?,
+    };
+
+    let (total_parsed, consumed, timings) = stream_lines(
+        py,
+        &mut reader,
+        chunk_size,
+        &callback,
+        total_lines,
+        profile,
+        start_line,
+        start_offset.is_some(),
+        on_error.as_ref(),
+        &options,
+    )?;
+    let position = position + consumed;
+
+    match (profile, start_offset) {
+        (true, Some(_)) => Ok((total_parsed, position, timings_to_dict(py, &timings)?).into_py(py)),
+        (true, None) => Ok((total_parsed, timings_to_dict(py, &timings)?).into_py(py)),
+        (false, Some(_)) => Ok((total_parsed, position).into_py(py)),
+        (false, None) => Ok(total_parsed.into_py(py)),
+    }
+}
+
+/// parse_file_streaming / parse_bytes_streaming 공통 본체 - reader를 끝까지 읽으며 chunk_size줄마다 콜백 호출
+/// hold_partial_line이면 개행 없는 마지막 줄은 읽지 않고 남겨 둠
+/// 반환: (콜백에 넘긴 항목 수, 소비한 바이트 수, profile 측정값)
+#[allow(clippy::too_many_arguments)]
+fn stream_lines(
+    py: Python<'_>,
+    reader: &mut dyn BufRead,
+    chunk_size: usize,
+    callback: &PyObject,
+    total_lines: usize,
+    profile: bool,
+    start_line: usize,
+    hold_partial_line: bool,
+    on_error: Option<&PyObject>,
+    options: &ParseOptions,
+) -> PyResult<(usize, u64, Vec<ChunkTiming>)> {
+    options.check_streaming()?;
+    let mut lines_buffer: Vec<String> = Vec::with_capacity(chunk_size);
+    let mut total_parsed = 0usize;
+    let mut current_line = 0usize;
+    let mut timings: Vec<ChunkTiming> = Vec::new();
+    let mut read_start = profile.then(Instant::now);
+    let mut state = ParseState::default();
+    // 연속 줄·중복을 합치는 옵션이면 청크의 마지막 항목은 다음 청크에서 갱신될 수 있으므로
+    // 콜백에 넘기지 않고 보류했다가 다음 청크 앞에 붙여 전달
+    // (merge_scope=pid/tid는 앞 청크의 다른 PID 항목도 갱신되므로 dict를 그대로 넘길 때만 허용 - check_chunked_output)
+    let mut output = ChunkOutput::default();
+    let mut stopped = false;
+
+    // 청크 파싱 + 콜백 호출. 콜백이 계속 진행을 원하면 true (변환 오류는 Err로 전파)
+    // is_last: 파일 끝의 마지막 호출 (보류 항목까지 모두 전달)
+    let mut flush = |lines_buffer: &mut Vec<String>, current_line: usize, read_start: &mut Option<Instant>, is_last: bool| -> PyResult<bool> {
+        let read_ms = read_start.map(elapsed_ms).unwrap_or(0.0);
+        let lines = lines_buffer.len();
+        if lines == 0 && !output.has_held() {
+            return Ok(true);
+        }
+
+        let parse_start = profile.then(Instant::now);
+        let parsed = output.take(lines_buffer.drain(..).filter_map(|l| parse_entry(py, &l, options, &mut state)), options, is_last);
+        state.take_error()?;
+        let parse_ms = parse_start.map(elapsed_ms).unwrap_or(0.0);
+
+        total_parsed += parsed.len();
+
+        // 콜백 호출: callback(parsed_logs, progress, total)
+        let callback_start = profile.then(Instant::now);
+        let total = total_lines.max(current_line);
+        let should_continue = match chunk_payload(py, parsed, options).and_then(|payload| callback.call1(py, (payload, current_line, total))) {
+            Ok(obj) => obj.extract::<bool>(py).unwrap_or(true),
+            Err(_) => false, // 에러 시 중단
+        };
+
+        if let Some(callback_start) = callback_start {
+            timings.push(ChunkTiming {
+                lines,
+                read_ms,
+                parse_ms,
+                callback_ms: elapsed_ms(callback_start),
+            });
+            *read_start = Some(Instant::now());
+        }
+        Ok(should_continue)
+    };
+
+    let mut position = 0u64;
+    let mut raw = Vec::new();
+    loop {
+        raw.clear();
+        let read = match read_line_limited(reader, &mut raw, options.max_line_length) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) => {
+                // 더 읽을 수 없는 오류(잘린 gzip 등) - 여기까지 읽은 만큼만 전달
+                report_read_error(py, on_error, current_line + 1, &e)?;
+                break;
+            }
+        };
+        // 오프셋으로 이어 읽는 경우 아직 기록 중인 마지막 줄은 다음 호출로 미룸
+        if hold_partial_line && raw.last() != Some(&b'\n') {
+            break;
+        }
+        position += read as u64;
+
+        // 체크포인트 이전 줄은 파싱하지 않고 줄 번호만 셈
+        if current_line < start_line {
+            current_line += 1;
+            continue;
+        }
+
+        if line_too_long(&raw, options.max_line_length) {
+            current_line += 1;
+            report_read_error(py, on_error, current_line, &line_too_long_error(options.max_line_length))?;
+            continue;
+        }
+
+        match std::str::from_utf8(&raw) {
+            Ok(line) => {
+                let trimmed = line.trim();
+                if !trimmed.is_empty() {
+                    lines_buffer.push(trimmed.to_string());
+                }
+                current_line += 1;
+
+                // chunk_size마다 콜백 호출
+                if lines_buffer.len() >= chunk_size && !flush(&mut lines_buffer, current_line, &mut read_start, false)? {
+                    stopped = true;
+                    break;
+                }
+            }
+            Err(e) => {
+                current_line += 1;
+                report_read_error(py, on_error, current_line, &e)?;
+            }
+        }
+    }
+
+    // 남은 라인 + 보류 항목 처리
+    if !stopped {
+        flush(&mut lines_buffer, current_line, &mut read_start, true)?;
+    }
+    Ok((total_parsed, position, timings))
+
+}
+
+/// 메모리의 바이트 버퍼를 parse_file_streaming과 같은 방식으로 파싱 (소켓으로 받은 logcat 등 파일 경로가 없는 경우)
+/// callback(parsed_logs, progress, total) -> bool, False면 중단. total은 버퍼의 줄 수
+///
+/// hold_partial=False면 개행 없는 마지막 줄도 그대로 파싱하고 total_parsed를 반환
+/// hold_partial=True면 마지막 조각은 파싱하지 않고 (total_parsed, consumed)를 반환
+/// → data[consumed:]를 다음에 받은 데이터 앞에 붙여 다시 호출하면 줄이 잘리지 않음
+///
+/// 버퍼는 UTF-8로 읽음 (gzip/encoding 미적용, 잘못된 UTF-8 줄은 on_error로 보고)
+/// 호출 사이에 상태를 이어 가지 않으므로 merge_*·collapse_duplicates는 호출 단위로만 합쳐짐
+#[pyfunction]
+#[pyo3(signature = (data, chunk_size, callback, hold_partial=false, on_error=None, **options))]
+fn parse_bytes_streaming(
+    py: Python<'_>,
+    data: &[u8],
+    chunk_size: usize,
+    callback: PyObject,
+    hold_partial: bool,
+    on_error: Option<PyObject>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<PyObject> {
+    let options = ParseOptions::from_kwargs(options)?;
+    let total_lines = if hold_partial {
+        count_lines_in(data) - data.last().is_some_and(|&b| b != b'\n') as usize
+    } else {
+        count_lines_in(data)
+    };
+
+    let mut reader = data;
+    let (total_parsed, consumed, _) = stream_lines(
+        py,
+        &mut reader,
+        chunk_size,
+        &callback,
+        total_lines,
+        false,
+        0,
+        hold_partial,
+        on_error.as_ref(),
+        &options,
+    )?;
+
+    if hold_partial {
+        Ok((total_parsed, consumed).into_py(py))
+    } else {
+        Ok(total_parsed.into_py(py))
+    }
+}
+
+/// offset 위치에서 파일을 열되, 줄 중간이면 다음 줄 시작까지 건너뜀
+/// 반환: (리더, 실제 시작 오프셋). 파일 크기를 넘는 오프셋은 파일 끝으로, UTF-8 BOM 안의 오프셋은 본문 시작으로 맞춤
+/// open_plain_reader와 같이 gzip·UTF-16 BOM 파일과 encoding은 ValueError
+fn open_at_line_boundary(file_path: &str, offset: u64, encoding: Option<&'static Encoding>) -> PyResult<(BufReader<File>, u64)> {
+    let (mut reader, body_start) = open_plain_reader(file_path, encoding)?;
+    let mut offset = offset.max(body_start).min(reader.get_ref().metadata()?.len());
+
+    // 본문 시작이거나 바로 앞 바이트가 개행이면 줄의 시작
+    let mut at_line_start = offset <= body_start;
+    if !at_line_start {
+        let mut prev = [0u8; 1];
+        reader.seek(SeekFrom::Start(offset - 1))?;
+        reader.read_exact(&mut prev)?;
+        at_line_start = prev[0] == b'\n';
+    }
+
+    reader.seek(SeekFrom::Start(offset))?;
+    if !at_line_start {
+        let mut partial = Vec::new();
+        reader.read_until(b'\n', &mut partial)?;
+        if partial.last() == Some(&b'\n') {
+            offset += partial.len() as u64;
+        } else {
+            // 개행이 없는 마지막 조각 - 다음 호출에서 다시 확인하도록 위치는 그대로
+            reader.seek(SeekFrom::Start(offset))?;
+        }
+    }
+    Ok((reader, offset))
+}
+
+/// message가 정규식에 맞는 항목만 골라 chunk_size개씩 콜백에 넘김 (뷰어 검색창용, 맞은 항목만 Python으로 보냄)
+/// callback(hits, current_line, total_lines) -> bool, False면 중단 (parse_file_streaming과 같은 인자)
+/// 각 항목에 match_spans: 맞은 모든 구간의 (start, end) 바이트 오프셋 리스트 (message의 UTF-8 기준, 겹치지 않음, 빈 매칭 제외)
+///
+/// 정규식 문법 오류·크기 한도 초과는 ValueError, ignore_case=True면 대소문자 무시 (패턴의 (?i)와 같음)
+/// 매칭은 옵션이 적용된 최종 message 기준. merge_* 옵션으로 나중에 이어 붙는 줄은 검색에 포함되지 않음
+/// on_error(line_number, message): parse_log_file_chunk와 같음 (검색이 끝난 뒤 호출)
+/// 반환: 콜백에 넘긴 항목 수
+#[pyfunction]
+#[pyo3(signature = (file_path, message_regex, chunk_size, callback, ignore_case=false, on_error=None, **options))]
+#[allow(clippy::too_many_arguments)]
+fn parse_file_search(
+    py: Python<'_>,
+    file_path: &str,
+    message_regex: &str,
+    chunk_size: usize,
+    callback: PyObject,
+    ignore_case: bool,
+    on_error: Option<PyObject>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<usize> {
+    let regex = if ignore_case {
+        compile_user_regex(&format!("(?i){}", message_regex))?
+    } else {
+        compile_user_regex(message_regex)?
+    };
+    let options = ParseOptions::from_kwargs(options)?;
+    options.check_streaming()?;
+    let reader = open_log_reader_as(file_path, options.encoding)?;
+    let total_lines = count_lines_as(file_path, options.encoding)?;
+
+    let mut state = ParseState::default();
+    let mut hits: Vec<PyObject> = Vec::new();
+    // sample_rate: 매칭된 항목 N개 중 1개 (청크가 바뀌어도 간격이 이어짐)
+    let mut sampled = 0usize;
+    let mut total_hits = 0usize;
+    let mut stopped = false;
+
+    let mut flush = |hits: &mut Vec<PyObject>, current_line: usize| -> bool {
+        if hits.is_empty() {
+            return true;
+        }
+        total_hits += hits.len();
+        let hits = output_entries(py, std::mem::take(hits), &options);
+        match callback.call1(py, (hits, current_line, total_lines.max(current_line))) {
+            Ok(obj) => obj.extract::<bool>(py).unwrap_or(true),
+            Err(_) => false, // 에러 시 중단
+        }
+    };
+
+    let mut lines = read_lines(reader, options.max_line_length);
+    while let Some(line) = lines.next() {
+        let current_line = lines.line_number;
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let entry = parse_entry(py, trimmed, &options, &mut state);
+        state.take_error()?;
+        let Some(entry) = entry else {
+            continue;
+        };
+
+        let spans: Vec<(usize, usize)> = {
+            let Ok(dict) = entry.bind(py).downcast::<PyDict>() else {
+                continue;
+            };
+            let Some(message) = dict.get_item("message")?.and_then(|message| message.extract::<String>().ok()) else {
+                continue;
+            };
+            // 빈 문자열 매칭(`a*` 등)은 강조할 구간이 없으므로 제외
+            regex
+                .find_iter(&message)
+                .filter(|found| !found.is_empty())
+                .map(|found| (found.start(), found.end()))
+                .collect()
+        };
+        if spans.is_empty() {
+            continue;
+        }
+        let Some(entry) = sample_every(vec![entry], options.sample_rate, &mut sampled).pop() else {
+            continue;
+        };
+        entry.bind(py).set_item("match_spans", spans)?;
+        hits.push(entry);
+
+        if hits.len() >= chunk_size.max(1) && !flush(&mut hits, current_line) {
+            stopped = true;
+            break;
+        }
+    }
+
+    if !stopped {
+        flush(&mut hits, lines.line_number);
+    }
+    lines.report(py, on_error.as_ref())?;
+    Ok(total_hits)
+}
+
+/// follow 중인 파일 핸들과 로테이션 감지를 위한 식별 정보
+struct FollowedFile {
+    reader: BufReader<File>,
+    identity: Option<(u64, u64)>,
+    position: u64,
+}
+
+impl FollowedFile {
+    fn open(file_path: &str) -> std::io::Result<Self> {
+        let file = File::open(file_path)?;
+        let identity = file_identity(&file.metadata()?);
+        Ok(Self {
+            reader: BufReader::new(file),
+            identity,
+            position: 0,
+        })
+    }
+
+    /// 현재 읽을 수 있는 데이터를 EOF까지 읽어 완성된 줄만 `lines`에 추가
+    /// 개행이 없는 마지막 조각은 `partial`에 보관해 다음 읽기에서 이어 붙임 (max_line_length가 있으면 그 길이까지만)
+    /// 읽기 오류는 (줄 번호, 오류)로 `errors`에 추가. line_number는 지금까지 읽은 줄 수
+    fn read_available(
+        &mut self,
+        partial: &mut Vec<u8>,
+        lines: &mut Vec<String>,
+        errors: &mut Vec<(usize, std::io::Error)>,
+        line_number: usize,
+        max_line_length: Option<usize>,
+    ) -> usize {
+        let mut read_lines = 0usize;
+        loop {
+            match read_line_limited(&mut self.reader, partial, max_line_length) {
+                Ok(0) => break,
+                Ok(n) => {
+                    self.position += n as u64;
+                    if partial.last() != Some(&b'\n') {
+                        // 아직 쓰는 중인 줄 - 개행이 올 때까지 보류
+                        break;
+                    }
+                    read_lines += 1;
+                    if let Some(e) = push_line(partial, lines, max_line_length) {
+                        errors.push((line_number + read_lines, e));
+                    }
+                }
+                Err(e) => {
+                    // 다음 폴링에서 다시 시도
+                    errors.push((line_number + read_lines + 1, e));
+                    break;
+                }
+            }
+        }
+        read_lines
+    }
+}
+
+/// 보류 중인 바이트를 한 줄로 확정해 버퍼에 추가 (빈 줄은 버림)
+/// max_line_length를 넘은 줄은 버리고 그 오류를 반환
+fn push_line(partial: &mut Vec<u8>, lines: &mut Vec<String>, max_line_length: Option<usize>) -> Option<std::io::Error> {
+    if line_too_long(partial, max_line_length) {
+        partial.clear();
+        return Some(line_too_long_error(max_line_length));
+    }
+    let text = String::from_utf8_lossy(partial);
+    let trimmed = text.trim();
+    if !trimmed.is_empty() {
+        lines.push(trimmed.to_string());
+    }
+    partial.clear();
+    None
+}
+
+#[cfg(unix)]
+fn file_identity(meta: &std::fs::Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((meta.dev(), meta.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_identity(_meta: &std::fs::Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+/// EOF에서 확인한 경로 상태
+enum RotationState {
+    /// 같은 파일이 계속 쓰이는 중
+    Unchanged,
+    /// 같은 파일이 잘려서 (copytruncate) 처음부터 다시 쓰임
+    Truncated,
+    /// 경로가 새 파일을 가리킴 (log.txt → log.txt.1 후 새 log.txt)
+    Rotated,
+}
+
+fn check_rotation(file_path: &str, followed: &FollowedFile) -> RotationState {
+    let meta = match std::fs::metadata(file_path) {
+        Ok(meta) => meta,
+        // 로테이션 도중 잠시 경로가 비어 있을 수 있음 - 다음 폴링에서 재확인
+        Err(_) => return RotationState::Unchanged,
+    };
+
+    match (followed.identity, file_identity(&meta)) {
+        (Some(old), Some(new)) if old != new => RotationState::Rotated,
+        (Some(_), Some(_)) if meta.len() < followed.position => RotationState::Truncated,
+        // inode를 알 수 없는 플랫폼은 크기 감소로만 판단
+        (None, _) | (_, None) if meta.len() < followed.position => RotationState::Rotated,
+        _ => RotationState::Unchanged,
+    }
+}
+
+/// 버퍼의 줄들을 파싱해 콜백으로 전달 (parse_file_streaming과 같은 보류·sample_rate·chunk_payload 경로)
+/// (전달한 개수, 계속 여부) 반환, 변환 오류는 Err로 전파
+fn deliver_lines(
+    py: Python<'_>,
+    callback: &PyObject,
+    options: &ParseOptions,
+    state: &mut ParseState,
+    output: &mut ChunkOutput,
+    lines_buffer: &mut Vec<String>,
+    current_line: usize,
+) -> PyResult<(usize, bool)> {
+    let parsed = output.take(lines_buffer.drain(..).filter_map(|l| parse_entry(py, &l, options, state)), options, false);
+    state.take_error()?;
+    let count = parsed.len();
+
+    let should_continue = match chunk_payload(py, parsed, options).and_then(|payload| callback.call1(py, (payload, current_line, current_line))) {
+        Ok(obj) => obj.extract::<bool>(py).unwrap_or(true),
+        Err(_) => false, // 에러 시 중단
+    };
+    Ok((count, should_continue))
+}
+
+/// 로테이션을 인식하며 기록 중인 파일을 계속 따라가며 파싱 (tail -F)
+/// callback(parsed_logs: List[Dict], current_line: int, total_lines: int) -> bool
+///
+/// - EOF에 도달하면 `poll_interval_ms`만큼 대기 후 새로 추가된 줄을 이어서 읽음
+/// - 경로의 inode가 바뀌면(로테이션) 이전 파일을 끝까지 읽은 뒤 새 파일의 처음부터 읽음
+/// - 같은 파일의 크기가 줄면(copytruncate) 처음부터 다시 읽음
+/// - 끝나지 않은 줄은 개행이 올 때까지 보류하므로 잘린 줄이 전달되지 않음
+/// - 전체 줄 수를 알 수 없으므로 total_lines는 지금까지 읽은 줄 수와 같음
+/// - 새 로그가 없어도 폴링마다 빈 리스트로 콜백을 호출하므로 언제든 False 반환으로 중단 가능
+/// - 청크는 parse_file_streaming과 같이 만듦 (as_objects/columnar/sample_rate, merge_* 등은 마지막 항목을 다음 폴링까지 보류)
+/// - on_error(line_number, message): 줄 읽기 오류·새 파일 열기 실패마다 호출 (없으면 stderr)
+#[pyfunction]
+#[pyo3(signature = (file_path, chunk_size, callback, poll_interval_ms=500, on_error=None, **options))]
+fn parse_rotating_follow(
+    py: Python<'_>,
+    file_path: &str,
+    chunk_size: usize,
+    callback: PyObject,
+    poll_interval_ms: u64,
+    on_error: Option<PyObject>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<usize> {
+    let options = ParseOptions::from_kwargs(options)?;
+    follow_file(py, file_path, chunk_size, &callback, poll_interval_ms, &options, on_error.as_ref(), true)
+}
+
+/// 기록 중인 파일을 계속 따라가며 파싱 (tail -f)
+/// callback(parsed_logs: List[Dict], current_line: int, total_lines: int) -> bool
+///
+/// parse_rotating_follow와 같지만 로테이션을 확인하지 않고 처음 연 파일만 따라감
+/// (adb logcat > file처럼 한 파일에 계속 덧붙이는 경우). 콜백이 False를 반환하면 중단
+#[pyfunction]
+#[pyo3(signature = (file_path, chunk_size, callback, poll_interval_ms=500, on_error=None, **options))]
+fn parse_file_follow(
+    py: Python<'_>,
+    file_path: &str,
+    chunk_size: usize,
+    callback: PyObject,
+    poll_interval_ms: u64,
+    on_error: Option<PyObject>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<usize> {
+    let options = ParseOptions::from_kwargs(options)?;
+    follow_file(py, file_path, chunk_size, &callback, poll_interval_ms, &options, on_error.as_ref(), false)
+}
+
+/// parse_rotating_follow / parse_file_follow 공통 루프 (detect_rotation: 로테이션·truncate 확인 여부)
+#[allow(clippy::too_many_arguments)]
+fn follow_file(
+    py: Python<'_>,
+    file_path: &str,
+    chunk_size: usize,
+    callback: &PyObject,
+    poll_interval_ms: u64,
+    options: &ParseOptions,
+    on_error: Option<&PyObject>,
+    detect_rotation: bool,
+) -> PyResult<usize> {
+    options.check_streaming()?;
+    let mut followed = FollowedFile::open(file_path)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("Failed to open file: {}", e)))?;
+
+    let chunk_size = chunk_size.max(1);
+    let poll_interval = Duration::from_millis(poll_interval_ms);
+    let mut partial: Vec<u8> = Vec::new();
+    let mut lines_buffer: Vec<String> = Vec::with_capacity(chunk_size);
+    let mut total_parsed = 0usize;
+    let mut current_line = 0usize;
+    let mut state = ParseState::default();
+    let mut output = ChunkOutput::default();
+    let mut read_errors: Vec<(usize, std::io::Error)> = Vec::new();
+
+    loop {
+        py.check_signals()?;
+
+        let mut read_lines =
+            followed.read_available(&mut partial, &mut lines_buffer, &mut read_errors, current_line, options.max_line_length);
+
+        let rotation = if detect_rotation { check_rotation(file_path, &followed) } else { RotationState::Unchanged };
+        match rotation {
+            RotationState::Unchanged => {}
+            RotationState::Truncated => {
+                followed.reader.seek(SeekFrom::Start(0))?;
+                followed.position = 0;
+                partial.clear();
+            }
+            RotationState::Rotated => {
+                // 이전 파일에 마지막으로 기록된 내용까지 모두 읽은 후 전환
+                read_lines += followed.read_available(
+                    &mut partial,
+                    &mut lines_buffer,
+                    &mut read_errors,
+                    current_line + read_lines,
+                    options.max_line_length,
+                );
+                if !partial.is_empty() {
+                    read_lines += 1;
+                    if let Some(e) = push_line(&mut partial, &mut lines_buffer, options.max_line_length) {
+                        read_errors.push((current_line + read_lines, e));
+                    }
+                }
+                match FollowedFile::open(file_path) {
+                    Ok(reopened) => followed = reopened,
+                    Err(e) => report_read_error(py, on_error, current_line + read_lines, &format!("Reopen error: {}", e))?,
+                }
+            }
+        }
+        current_line += read_lines;
+        for (line_number, error) in read_errors.drain(..) {
+            report_read_error(py, on_error, line_number, &error)?;
+        }
+
+        // chunk_size 단위로 나눠 전달하고, 남은 줄은 이번 폴링에서 모두 전달
+        loop {
+            let take = lines_buffer.len().min(chunk_size);
+            let mut chunk: Vec<String> = lines_buffer.drain(..take).collect();
+            let (count, should_continue) = deliver_lines(py, callback, options, &mut state, &mut output, &mut chunk, current_line)?;
+            total_parsed += count;
+            if !should_continue {
+                return Ok(total_parsed);
+            }
+            if lines_buffer.is_empty() {
+                break;
+            }
+        }
+
+        if read_lines == 0 {
+            py.allow_threads(|| std::thread::sleep(poll_interval));
+        }
+    }
+}
+
+// 사용자 정규식(regex_extract 등) 컴파일 한도 (바이트): (컴파일된 프로그램 크기, 지연 DFA 캐시 크기)
+// regex 크레이트는 백트래킹 없이 줄 길이에 선형 시간으로 매칭하므로 매칭 시간 폭주는 없고,
+// 남는 위험은 `(a{1000}){1000}` 같은 패턴의 컴파일 크기·캐시 메모리라 이를 제한
+static USER_REGEX_LIMITS: Lazy<RwLock<(usize, usize)>> = Lazy::new(|| RwLock::new((1 << 20, 2 << 20)));
+
+// 한 줄의 사용자 형식 매칭 시간 한도 (None이면 무제한)
+// 매칭 도중에는 멈출 수 없으므로 형식 하나를 시도할 때마다 누적 시간을 확인
+static USER_REGEX_MATCH_TIMEOUT: Lazy<RwLock<Option<Duration>>> = Lazy::new(|| RwLock::new(Some(Duration::from_millis(100))));
+
+/// 사용자 형식 매칭이 USER_REGEX_MATCH_TIMEOUT을 넘겨 포기한 줄
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct RegexTimeout;
+
+/// 사용자 정규식을 현재 한도로 컴파일 (한도 초과·문법 오류는 ValueError)
+fn compile_user_regex(pattern: &str) -> PyResult<Regex> {
+    let (size_limit, dfa_size_limit) = *USER_REGEX_LIMITS.read().unwrap();
+    RegexBuilder::new(pattern)
+        .size_limit(size_limit)
+        .dfa_size_limit(dfa_size_limit)
+        .build()
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid regex: {}", e)))
+}
+
+/// 사용자 정규식 한도 설정, 현재 (size_limit, dfa_size_limit, match_timeout_ms) 반환
+/// - size_limit: 컴파일된 정규식 크기 상한, 넘으면 등록 시 ValueError (기본 1MiB, 이후 등록하는 패턴부터 적용)
+/// - dfa_size_limit: 매칭 중 지연 DFA 캐시 상한, 넘으면 느린 엔진으로 전환될 뿐 실패하지 않음 (기본 2MiB, 이후 등록하는 패턴부터 적용)
+/// - match_timeout_ms: 한 줄의 사용자 형식 매칭 시간 한도, 넘긴 줄은 regex_timeout 항목으로 남김 (기본 100, 0이면 무제한, 바로 적용)
+#[pyfunction]
+#[pyo3(signature = (size_limit=None, dfa_size_limit=None, match_timeout_ms=None))]
+fn set_regex_limits(size_limit: Option<usize>, dfa_size_limit: Option<usize>, match_timeout_ms: Option<u64>) -> (usize, usize, u64) {
+    let mut limits = USER_REGEX_LIMITS.write().unwrap();
+    if let Some(size_limit) = size_limit {
+        limits.0 = size_limit;
+    }
+    if let Some(dfa_size_limit) = dfa_size_limit {
+        limits.1 = dfa_size_limit;
+    }
+    let mut timeout = USER_REGEX_MATCH_TIMEOUT.write().unwrap();
+    if let Some(match_timeout_ms) = match_timeout_ms {
+        *timeout = (match_timeout_ms > 0).then(|| Duration::from_millis(match_timeout_ms));
+    }
+    let timeout_ms = timeout.map_or(0, |timeout| timeout.as_millis() as u64);
+    (limits.0, limits.1, timeout_ms)
+}
+
+/// 파싱 직후 결과 dict에 등록 순서대로 적용되는 변환
+enum Transform {
+    /// 필드 이름 변경 (없는 필드면 무시)
+    Rename { from: String, to: String },
+    /// 필드 제거
+    DropField { field: String },
+    /// 고정 값 필드 추가 (이미 있으면 덮어씀)
+    AddConstant { field: String, value: PyObject },
+    /// 문자열 필드에 정규식을 적용해 이름 있는 그룹을 새 필드로 추가
+    RegexExtract { field: String, pattern: Regex },
+}
+
+// 모듈 함수 add_transform(사용 중단 예정)으로 등록한 전역 변환 - 모든 파싱 함수와 LogParser 인스턴스에 적용
+static TRANSFORMS: Lazy<RwLock<Vec<Transform>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+fn transform_param<'py>(params: Option<&Bound<'py, PyDict>>, op: &str, name: &str) -> PyResult<Bound<'py, PyAny>> {
+    params
+        .and_then(|params| params.get_item(name).ok().flatten())
+        .ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Transform '{}' requires '{}'", op, name))
+        })
+}
 
-// 정규식 패턴들을 한 번만 컴파일 (성능 최적화)
-static TIME_PATTERN: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"(\d{2}-\d{2}\s+\d{2}:\d{2}:\d{2}\.\d{3})").unwrap()
-});
+impl Transform {
+    fn from_params(op: &str, params: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+        let string_param = |name: &str| -> PyResult<String> { transform_param(params, op, name)?.extract() };
 
-static THREADTIME_SIMPLE: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"^(\d+)\s+-\s+-\s+([^:]+):\s+(.*)$").unwrap()
-});
+        match op {
+            "rename" => Ok(Transform::Rename {
+                from: string_param("field")?,
+                to: string_param("to")?,
+            }),
+            "drop_field" => Ok(Transform::DropField {
+                field: string_param("field")?,
+            }),
+            "add_constant" => Ok(Transform::AddConstant {
+                field: string_param("field")?,
+                value: transform_param(params, op, "value")?.unbind(),
+            }),
+            "regex_extract" => {
+                let pattern = string_param("pattern")?;
+                let pattern = compile_user_regex(&pattern)?;
+                if pattern.capture_names().flatten().next().is_none() {
+                    return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                        "regex_extract pattern needs at least one named group (?P<name>...)",
+                    ));
+                }
+                Ok(Transform::RegexExtract {
+                    field: string_param("field")?,
+                    pattern,
+                })
+            }
+            _ => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Unknown transform '{}' (expected rename, drop_field, add_constant, regex_extract)",
+                op
+            ))),
+        }
+    }
 
-static THREADTIME_COMPLEX: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"^([VDIWEAF])\s+-\s+-\s+(\d+)\s+(\d+)\s+([VDIWEAF])\s+([^:]+):\s*(.*)$").unwrap()
-});
+    /// 다른 파서에 옮길 복사본 (AddConstant 값은 같은 Python 객체를 가리킴)
+    fn clone_ref(&self, py: Python<'_>) -> Self {
+        match self {
+            Transform::Rename { from, to } => Transform::Rename {
+                from: from.clone(),
+                to: to.clone(),
+            },
+            Transform::DropField { field } => Transform::DropField { field: field.clone() },
+            Transform::AddConstant { field, value } => Transform::AddConstant {
+                field: field.clone(),
+                value: value.clone_ref(py),
+            },
+            Transform::RegexExtract { field, pattern } => Transform::RegexExtract {
+                field: field.clone(),
+                pattern: pattern.clone(),
+            },
+        }
+    }
 
-static LEVEL_TAG_PATTERN: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"^([DIWEFV])/([^(]+)\(\s*([^)]*?)\s*\)\s+(.*)$").unwrap()
-});
+    fn apply(&self, dict: &Bound<'_, PyDict>) -> PyResult<()> {
+        match self {
+            Transform::Rename { from, to } => {
+                if let Some(value) = dict.get_item(from)? {
+                    dict.del_item(from)?;
+                    dict.set_item(to, value)?;
+                }
+            }
+            Transform::DropField { field } => {
+                if dict.contains(field)? {
+                    dict.del_item(field)?;
+                }
+            }
+            Transform::AddConstant { field, value } => dict.set_item(field, value)?,
+            Transform::RegexExtract { field, pattern } => {
+                let Some(value) = dict.get_item(field)? else {
+                    return Ok(());
+                };
+                let Ok(text) = value.extract::<String>() else {
+                    return Ok(());
+                };
+                if let Some(caps) = pattern.captures(&text) {
+                    for name in pattern.capture_names().flatten() {
+                        if let Some(m) = caps.name(name) {
+                            dict.set_item(name, m.as_str())?;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
 
-static DISPLAY_PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
-    vec![
-        Regex::new(r"(?i)displayId[:\s]+(\d+)").unwrap(),
-        Regex::new(r"(?i)display[:\s]+(\d+)").unwrap(),
-        Regex::new(r"(?i)Display\s+(\d+)").unwrap(),
-    ]
-});
+/// (사용 중단 예정) 모든 파싱 함수·LogParser에 공통으로 적용할 전역 변환 등록, 등록된 변환 개수 반환
+/// 파서마다 다른 변환은 LogParser.add_transform을 사용 - 호출 시 DeprecationWarning
+/// - add_transform("rename", field="tag", to="component")
+/// - add_transform("drop_field", field="display")
+/// - add_transform("add_constant", field="source", value="ivi")
+/// - add_transform("regex_extract", field="message", pattern=r"took (?P<took_ms>\d+)ms")
+#[pyfunction]
+#[pyo3(signature = (op, **params))]
+fn add_transform(py: Python<'_>, op: &str, params: Option<&Bound<'_, PyDict>>) -> PyResult<usize> {
+    PyErr::warn_bound(
+        py,
+        &py.get_type_bound::<pyo3::exceptions::PyDeprecationWarning>(),
+        "module-level add_transform applies to every parser; use LogParser.add_transform",
+        1,
+    )?;
+    let transform = Transform::from_params(op, params)?;
+    let mut transforms = TRANSFORMS.write().unwrap();
+    transforms.push(transform);
+    Ok(transforms.len())
+}
 
-/// 로그 라인을 파싱하여 딕셔너리로 반환
+/// 전역 변환 모두 제거 (LogParser.add_transform으로 등록한 변환은 그대로)
 #[pyfunction]
-fn parse_log_line(line: &str) -> Option<PyObject> {
-    Python::with_gil(|py| {
-        let line = line.trim();
-        if line.is_empty() {
-            return None;
-        }
+fn clear_transforms() {
+    TRANSFORMS.write().unwrap().clear();
+}
 
-        // 시간 패턴 찾기
-        let time_match = TIME_PATTERN.find(line)?;
-        let timestamp = time_match.as_str();
-        let remaining = &line[time_match.end()..].trim();
+/// 전역 변환, 그다음 파서별 변환(options.transforms)을 등록 순서대로 적용
+fn apply_transforms(dict: &Bound<'_, PyDict>, options: &ParseOptions) -> PyResult<()> {
+    let transforms = TRANSFORMS.read().unwrap();
+    for transform in transforms.iter().chain(options.transforms.iter()) {
+        transform.apply(dict)?;
+    }
+    Ok(())
+}
 
-        // 형식 1: mm-dd HH:MM:SS.mmm  PID  -  -  Tag: Message (Level 없음)
-        if let Some(caps) = THREADTIME_SIMPLE.captures(remaining) {
-            let pid = caps.get(1)?.as_str();
-            let tag = caps.get(2)?.as_str().trim();
-            let message = caps.get(3)?.as_str().trim();
-            let display = classify_display(tag, message);
+/// add_custom_format으로 등록한 사용자 형식 (등록 시 한 번 컴파일)
+struct CustomFormat {
+    regex: Regex,
+    /// 표준 필드 외에 dict에 추가할 이름 있는 그룹
+    extra_fields: Vec<String>,
+}
 
-            let dict = PyDict::new_bound(py);
-            dict.set_item("timestamp", timestamp).ok()?;
-            dict.set_item("level", "-").ok()?;
-            dict.set_item("pid", pid).ok()?;
-            dict.set_item("tid", "-").ok()?;
-            dict.set_item("tag", tag).ok()?;
-            dict.set_item("message", message).ok()?;
-            dict.set_item("display", display).ok()?;
-            return Some(dict.into());
-        }
-
-        // 형식 2: mm-dd HH:MM:SS.mmm  Level  -  -  PID  TID  Level  Tag: Message
-        if let Some(caps) = THREADTIME_COMPLEX.captures(remaining) {
-            let level = caps.get(4)?.as_str();
-            let pid = caps.get(2)?.as_str();
-            let tid = caps.get(3)?.as_str();
-            let tag = caps.get(5)?.as_str().trim();
-            let message = caps.get(6)?.as_str().trim();
-            let display = classify_display(tag, message);
+/// 사용자 형식의 이름 있는 그룹 중 표준 필드로 쓰는 이름
+const CUSTOM_STANDARD_FIELDS: [&str; 7] = ["timestamp", "level", "pid", "tid", "tag", "message", "uid"];
 
-            let dict = PyDict::new_bound(py);
-            dict.set_item("timestamp", timestamp).ok()?;
-            dict.set_item("level", level).ok()?;
-            dict.set_item("pid", pid).ok()?;
-            dict.set_item("tid", tid).ok()?;
-            dict.set_item("tag", tag).ok()?;
-            dict.set_item("message", message).ok()?;
-            dict.set_item("display", display).ok()?;
-            return Some(dict.into());
-        }
-
-        // 형식 3: Level/Tag(  PID  TID  Message
-        if let Some(caps) = LEVEL_TAG_PATTERN.captures(remaining) {
-            let level = caps.get(1)?.as_str();
-            let tag = caps.get(2)?.as_str().trim();
-            let pid_tid = caps.get(3)?.as_str().trim();
-            let message = caps.get(4)?.as_str().trim();
-
-            let pid_tid_parts: Vec<&str> = pid_tid.split_whitespace().collect();
-            let pid = pid_tid_parts.get(0).unwrap_or(&"-");
-            let tid = pid_tid_parts.get(1).unwrap_or(&"-");
-            let display = classify_display(tag, message);
+// 모듈(프로세스) 전역 - 모든 파싱·스캔 함수와 LogParser 인스턴스가 같은 목록을 씀
+static CUSTOM_FORMATS: Lazy<RwLock<Vec<CustomFormat>>> = Lazy::new(|| RwLock::new(Vec::new()));
 
-            let dict = PyDict::new_bound(py);
-            dict.set_item("timestamp", timestamp).ok()?;
-            dict.set_item("level", level).ok()?;
-            dict.set_item("pid", *pid).ok()?;
-            dict.set_item("tid", *tid).ok()?;
-            dict.set_item("tag", tag).ok()?;
-            dict.set_item("message", message).ok()?;
-            dict.set_item("display", display).ok()?;
-            return Some(dict.into());
+/// 등록 순서대로 사용자 형식을 시도해 처음 맞는 형식의 필드 추출
+/// 표준 그룹이 없으면 timestamp/level/pid/tid는 "-", tag는 "", message는 줄 전체
+/// 시도한 형식들의 누적 매칭 시간이 match_timeout_ms를 넘으면 Err(RegexTimeout)
+fn match_custom_fields(line: &str) -> Result<Option<LogFields<'_>>, RegexTimeout> {
+    let formats = CUSTOM_FORMATS.read().unwrap();
+    if formats.is_empty() {
+        return Ok(None);
+    }
+    let timeout = *USER_REGEX_MATCH_TIMEOUT.read().unwrap();
+    let start = timeout.map(|_| Instant::now());
+    for (index, format) in formats.iter().enumerate() {
+        let caps = format.regex.captures(line);
+        if let (Some(timeout), Some(start)) = (timeout, start) {
+            if start.elapsed() > timeout {
+                return Err(RegexTimeout);
+            }
         }
+        let Some(caps) = caps else {
+            continue;
+        };
+        let field = |name: &str| caps.name(name).map(|value| value.as_str().trim());
+        return Ok(Some(LogFields {
+            line,
+            timestamp: field("timestamp").unwrap_or("-"),
+            level: field("level").unwrap_or("-"),
+            pid: field("pid").unwrap_or("-"),
+            tid: field("tid").unwrap_or("-"),
+            tag: field("tag").unwrap_or(""),
+            message: field("message").unwrap_or(line),
+            uid: field("uid"),
+            custom_format: Some(index),
+        }));
+    }
+    Ok(None)
+}
 
-        None
-    })
+/// 사용자 형식의 추가 그룹을 dict에 넣음 (매칭에 참여하지 않은 그룹은 생략)
+fn add_custom_fields(dict: &Bound<'_, PyDict>, line: &str, index: usize) -> PyResult<()> {
+    let formats = CUSTOM_FORMATS.read().unwrap();
+    let Some(format) = formats.get(index) else {
+        return Ok(());
+    };
+    let Some(caps) = format.regex.captures(line) else {
+        return Ok(());
+    };
+    for name in &format.extra_fields {
+        if let Some(value) = caps.name(name) {
+            dict.set_item(name.as_str(), value.as_str())?;
+        }
+    }
+    Ok(())
 }
 
-/// 배치 파싱 (벡터화된 처리로 더 빠름)
+/// 사용자 형식 등록, 등록된 형식 수 반환
+/// 내장 형식이 모두 실패한 줄에만 등록 순서대로 시도 (formats 옵션과 무관)
+/// - 이름 있는 그룹 timestamp/level/pid/tid/tag/message/uid는 표준 필드로 사용
+/// - field_names: dict에 추가할 그룹 이름 (패턴에 없는 이름이면 ValueError)
 #[pyfunction]
-fn parse_log_batch(lines: Vec<String>) -> Vec<PyObject> {
-    lines
-        .into_iter()
-        .filter_map(|line| parse_log_line(&line))
-        .collect()
+#[pyo3(signature = (pattern, field_names=None))]
+fn add_custom_format(pattern: &str, field_names: Option<Vec<String>>) -> PyResult<usize> {
+    let regex = compile_user_regex(pattern)?;
+    let mut extra_fields = Vec::new();
+    for name in field_names.unwrap_or_default() {
+        if !regex.capture_names().flatten().any(|group| group == name) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "field '{}' is not a named group in pattern",
+                name
+            )));
+        }
+        if !CUSTOM_STANDARD_FIELDS.contains(&name.as_str()) && !extra_fields.contains(&name) {
+            extra_fields.push(name);
+        }
+    }
+
+    let mut formats = CUSTOM_FORMATS.write().unwrap();
+    formats.push(CustomFormat { regex, extra_fields });
+    Ok(formats.len())
 }
 
-/// 파일에서 로그를 읽고 파싱 (고성능 파일 I/O + 파싱)
-/// 배치 단위로 결과를 반환하여 메모리 효율적 처리
+/// 등록된 사용자 형식 모두 제거
 #[pyfunction]
-fn parse_log_file_chunk(file_path: &str, batch_size: usize) -> PyResult<Vec<PyObject>> {
-    // 파일 읽기 (GIL 밖에서 수행)
-    let file = File::open(file_path)
-        .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("Failed to open file: {}", e)))?;
-    
-    let reader = BufReader::new(file);
-    let mut lines = Vec::new();
-    
-    for line in reader.lines() {
-        match line {
-            Ok(line) => {
-                let trimmed = line.trim();
-                if !trimmed.is_empty() {
-                    lines.push(trimmed.to_string());  // 소유권 확보
-                }
-            }
-            Err(e) => {
-                // 읽기 오류는 로그하고 계속 진행
-                eprintln!("Line read error: {}", e);
-            }
-        }
+fn clear_custom_formats() {
+    CUSTOM_FORMATS.write().unwrap().clear();
+}
+
+/// 태그 + PID 조합 디스플레이 규칙
+struct CompositeDisplayRule {
+    /// 소문자로 저장된 태그 부분 문자열 (빈 문자열이면 모든 태그)
+    tag_substring: String,
+    /// 정확히 일치해야 하는 PID (None이면 모든 PID)
+    pid: Option<String>,
+    display: String,
+}
+
+impl CompositeDisplayRule {
+    /// PID + 태그 > PID만 > 태그만, 같은 단계에서는 긴 태그 문자열이 우선
+    fn specificity(&self) -> (u8, usize) {
+        let level = match (self.pid.is_some(), !self.tag_substring.is_empty()) {
+            (true, true) => 3,
+            (true, false) => 2,
+            _ => 1,
+        };
+        (level, self.tag_substring.len())
     }
-    
-    // 파싱 (GIL 필요)
-    Python::with_gil(|_py| {
-        let mut results = Vec::new();
-        
-        // 배치 단위로 파싱
-        for chunk in lines.chunks(batch_size) {
-            let parsed: Vec<PyObject> = chunk
-                .iter()
-                .filter_map(|l| parse_log_line(l))
-                .collect();
-            results.extend(parsed);
+
+    fn matches(&self, tag_lower: &str, pid: &str) -> bool {
+        self.pid.as_deref().is_none_or(|p| p == pid) && tag_lower.contains(&self.tag_substring)
+    }
+}
+
+// 구체적인 규칙이 앞에 오도록 정렬된 상태로 보관
+// 모듈(프로세스) 전역 - 모든 파싱 함수와 LogParser 인스턴스가 같은 규칙을 씀
+static COMPOSITE_DISPLAY_RULES: Lazy<RwLock<Vec<CompositeDisplayRule>>> =
+    Lazy::new(|| RwLock::new(Vec::new()));
+
+/// 태그 + PID 조합 디스플레이 규칙 설정 (기존 규칙은 교체, 빈 리스트면 해제, 모듈 전역)
+/// rules: [(tag_substring, pid, display), ...]
+///
+/// - tag_substring: 대소문자 무시 부분 일치, 빈 문자열이면 모든 태그
+/// - pid: 정확히 일치 (int 또는 str), None이면 모든 PID
+/// - 가장 구체적인 규칙이 먼저 적용: PID+태그 > PID만 > 태그만 (같으면 긴 태그 문자열, 그다음 입력 순서)
+/// - 조합 규칙은 메시지의 displayId, 태그 키워드 분류보다 먼저 적용됨
+#[pyfunction]
+fn set_composite_display_rules(rules: Vec<(String, Option<Bound<'_, PyAny>>, String)>) -> PyResult<()> {
+    let mut parsed = Vec::with_capacity(rules.len());
+    for (tag_substring, pid, display) in rules {
+        let pid = match pid {
+            Some(pid) => Some(pid.str()?.to_string()),
+            None => None,
+        };
+        if tag_substring.is_empty() && pid.is_none() {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "Composite display rule needs a tag_substring or a pid",
+            ));
         }
-        
-        Ok(results)
-    })
+        parsed.push(CompositeDisplayRule {
+            tag_substring: tag_substring.to_lowercase(),
+            pid,
+            display,
+        });
+    }
+    // 안정 정렬이라 같은 구체성이면 입력 순서 유지
+    parsed.sort_by_key(|rule| std::cmp::Reverse(rule.specificity()));
+
+    *COMPOSITE_DISPLAY_RULES.write().unwrap() = parsed;
+    Ok(())
 }
 
-/// 파일의 총 줄 수를 빠르게 계산
+// PID → 프로세스(패키지) 이름. 비어 있지 않으면 파싱 결과에 process 추가
+static PID_MAP: Lazy<RwLock<HashMap<String, String>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// PID → 프로세스 이름 맵 설정 (기존 맵은 교체, 빈 dict면 해제)
 #[pyfunction]
-fn count_file_lines(file_path: &str) -> PyResult<usize> {
-    let file = File::open(file_path)
-        .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("Failed to open file: {}", e)))?;
-    
-    let reader = BufReader::new(file);
-    let count = reader.lines().count();
-    Ok(count)
+fn set_pid_map(map: HashMap<String, String>) {
+    *PID_MAP.write().unwrap() = map;
 }
 
-/// 파일을 한 번만 읽고 청크마다 콜백 호출 (O(n) - 가장 효율적)
-/// callback(parsed_logs: List[Dict], progress: int, total: int) -> bool
-/// 콜백이 False 반환하면 중단
+/// `ps` 출력 또는 `pid package` 형식 파일에서 PID 맵을 읽어 설정 (기존 맵은 교체)
+/// 반환: 읽은 매핑 개수
+///
+/// - 첫 줄에 `PID` 열이 있으면 `ps` 출력으로 보고 PID 열과 `NAME`/`CMD`/`COMMAND`/`ARGS` 열
+///   (없으면 마지막 열)을 사용 (`ps -A`, `ps -ef` 등)
+/// - 그 외에는 한 줄에 `pid package` (공백 구분, `#` 주석과 빈 줄 무시)
+/// - PID가 숫자가 아닌 줄은 건너뜀, 같은 PID가 여러 번 나오면 마지막 값
+/// - on_error(line_number, message): parse_log_file_chunk와 같음
 #[pyfunction]
-fn parse_file_streaming(
-    file_path: &str, 
-    chunk_size: usize, 
-    callback: PyObject
-) -> PyResult<usize> {
-    let file = File::open(file_path)
-        .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("Failed to open file: {}", e)))?;
-    
-    // 먼저 총 줄 수 계산 (진행률용)
-    let total_lines = {
-        let file = File::open(file_path)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("Failed to open file: {}", e)))?;
-        BufReader::new(file).lines().count()
-    };
-    
-    let reader = BufReader::new(file);
-    let mut lines_buffer: Vec<String> = Vec::with_capacity(chunk_size);
-    let mut total_parsed = 0usize;
-    let mut current_line = 0usize;
-    
-    for line in reader.lines() {
-        match line {
-            Ok(line) => {
-                let trimmed = line.trim();
-                if !trimmed.is_empty() {
-                    lines_buffer.push(trimmed.to_string());
+#[pyo3(signature = (path, on_error=None))]
+fn load_pid_map_from_file(py: Python<'_>, path: &str, on_error: Option<PyObject>) -> PyResult<usize> {
+    let file = open_log_reader(path)?;
+    let mut lines = read_lines(file, None);
+
+    let map = py.allow_threads(|| {
+        let mut map = HashMap::new();
+        // ps 헤더가 있으면 (PID 열, 이름 열)
+        let mut columns: Option<(usize, Option<usize>)> = None;
+        let mut first = true;
+
+        for line in lines.by_ref() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            let cols: Vec<&str> = trimmed.split_whitespace().collect();
+
+            if first {
+                first = false;
+                if let Some(pid_col) = cols.iter().position(|c| *c == "PID") {
+                    let name_col = cols
+                        .iter()
+                        .position(|c| matches!(*c, "NAME" | "CMD" | "COMMAND" | "ARGS"));
+                    columns = Some((pid_col, name_col));
+                    continue;
                 }
-                current_line += 1;
-                
-                // chunk_size마다 콜백 호출
-                if lines_buffer.len() >= chunk_size {
-                    let should_continue = Python::with_gil(|py| {
-                        // 파싱
-                        let parsed: Vec<PyObject> = lines_buffer
-                            .drain(..)
-                            .filter_map(|l| parse_log_line(&l))
-                            .collect();
-                        
-                        let count = parsed.len();
-                        total_parsed += count;
-                        
-                        // 콜백 호출: callback(parsed_logs, progress, total)
-                        let result = callback.call1(py, (parsed, current_line, total_lines));
-                        
-                        match result {
-                            Ok(obj) => obj.extract::<bool>(py).unwrap_or(true),
-                            Err(_) => false, // 에러 시 중단
-                        }
-                    });
-                    
-                    if !should_continue {
-                        return Ok(total_parsed);
+            }
+
+            let (pid, name) = match columns {
+                Some((pid_col, name_col)) => {
+                    let name_col = name_col.unwrap_or(cols.len() - 1);
+                    match (cols.get(pid_col), cols.get(name_col..)) {
+                        (Some(pid), Some(name)) if !name.is_empty() => (*pid, name.join(" ")),
+                        _ => continue,
                     }
                 }
+                None if cols.len() >= 2 => (cols[0], cols[1..].join(" ")),
+                None => continue,
+            };
+            if pid.bytes().all(|b| b.is_ascii_digit()) {
+                map.insert(pid.to_string(), name);
             }
-            Err(e) => {
-                eprintln!("Line read error: {}", e);
-                current_line += 1;
+        }
+        map
+    });
+    lines.report(py, on_error.as_ref())?;
+
+    let count = map.len();
+    *PID_MAP.write().unwrap() = map;
+    Ok(count)
+}
+
+/// 한 줄에서 (PID, 프로세스 이름) 추출
+/// - events 버퍼 `am_proc_start: [user,pid,uid,process,type,component]` (4.2 이전은 user 없이 5개)
+/// - main 버퍼 ActivityManager `Start proc <pid>:<process>/<uid> for ...`
+fn proc_start_mapping<'a>(fields: &LogFields<'a>) -> Option<(&'a str, &'a str)> {
+    if fields.tag == "am_proc_start" {
+        let body = fields.message.trim().strip_prefix('[')?.strip_suffix(']')?;
+        let parts: Vec<&str> = body.split(',').map(str::trim).collect();
+        let (pid, name) = match parts.len() {
+            n if n >= 6 => (parts[1], parts[3]),
+            5 => (parts[0], parts[2]),
+            _ => return None,
+        };
+        return pid.bytes().all(|b| b.is_ascii_digit()).then_some((pid, name));
+    }
+
+    let caps = START_PROC_PATTERN.captures(fields.message)?;
+    Some((caps.get(1)?.as_str(), caps.get(2)?.as_str()))
+}
+
+/// 로그 자체의 프로세스 시작 이벤트(am_proc_start, ActivityManager "Start proc")로 PID 맵 생성
+/// 외부 ps 스냅샷 없이 set_pid_map에 넘길 맵을 만들기 위함
+/// PID가 재사용되면 나중 매핑이 남음. 스캔은 GIL 없이 수행
+/// on_error(line_number, message): parse_log_file_chunk와 같음
+#[pyfunction]
+#[pyo3(signature = (file_path, on_error=None))]
+fn build_pid_map_from_log(
+    py: Python<'_>,
+    file_path: &str,
+    on_error: Option<PyObject>,
+) -> PyResult<HashMap<String, String>> {
+    let file = open_log_reader(file_path)?;
+    let mut lines = read_lines(file, None);
+
+    let map = py.allow_threads(|| {
+        let mut map = HashMap::new();
+        for line in lines.by_ref() {
+            let Some(fields) = parse_line_inner(&line) else {
+                continue;
+            };
+            if let Some((pid, name)) = proc_start_mapping(&fields) {
+                map.insert(pid.to_string(), name.to_string());
             }
         }
+        map
+    });
+    lines.report(py, on_error.as_ref())?;
+    Ok(map)
+}
+
+/// AAOS 다중 디스플레이 자동 분류
+/// 우선순위: 태그+PID 조합 규칙 → 메시지의 displayId → 태그 키워드 → Main
+/// display_map이 있으면 displayId 이름을 기본 매핑보다 먼저 찾음
+fn classify_display(tag: &str, pid: &str, message: &str, display_map: Option<&HashMap<String, String>>) -> Cow<'static, str> {
+    match_display_detail(tag, pid, message, display_map)
+        .map(|matched| matched.display)
+        .unwrap_or(Cow::Borrowed("Main"))
+}
+
+/// 디스플레이 분류 결과와 어느 규칙이 맞았는지 (display_source 옵션)
+struct DisplayMatch<'a> {
+    display: Cow<'static, str>,
+    /// "composite_rule" | "display_id" | "tag_cluster" | "tag_ivi" | "tag_passenger" | "sticky" | "default"
+    source: &'static str,
+    /// 메시지에서 찾은 displayId 원본 (source가 "display_id"일 때)
+    display_id: Option<&'a str>,
+}
+
+impl DisplayMatch<'_> {
+    fn new(display: Cow<'static, str>, source: &'static str) -> Self {
+        Self {
+            display,
+            source,
+            display_id: None,
+        }
     }
-    
-    // 남은 라인 처리
-    if !lines_buffer.is_empty() {
-        Python::with_gil(|py| {
-            let parsed: Vec<PyObject> = lines_buffer
-                .into_iter()
-                .filter_map(|l| parse_log_line(&l))
-                .collect();
-            
-            total_parsed += parsed.len();
-            let _ = callback.call1(py, (parsed, current_line, total_lines));
-        });
+}
+
+/// 아무 근거가 없을 때의 기본값 Main
+impl Default for DisplayMatch<'_> {
+    fn default() -> Self {
+        Self::new(Cow::Borrowed("Main"), "default")
     }
-    
-    Ok(total_parsed)
 }
 
-/// AAOS 다중 디스플레이 자동 분류
-fn classify_display(tag: &str, message: &str) -> &'static str {
+/// 분류 규칙 중 하나라도 맞으면 해당 디스플레이와 근거, 아무 근거가 없으면 None (기본값 Main 대상)
+fn match_display_detail<'a>(
+    tag: &str,
+    pid: &str,
+    message: &'a str,
+    display_map: Option<&HashMap<String, String>>,
+) -> Option<DisplayMatch<'a>> {
+    let tag_lower = tag.to_lowercase();
+
+    let rules = COMPOSITE_DISPLAY_RULES.read().unwrap();
+    if let Some(rule) = rules.iter().find(|rule| rule.matches(&tag_lower, pid)) {
+        return Some(DisplayMatch::new(Cow::Owned(rule.display.clone()), "composite_rule"));
+    }
+    drop(rules);
+
     // Display ID 패턴 찾기
     for pattern in DISPLAY_PATTERNS.iter() {
         if let Some(caps) = pattern.captures(message) {
             if let Some(display_id) = caps.get(1) {
-                match display_id.as_str() {
-                    "0" => return "Main",
-                    "1" => return "Cluster",
-                    "2" => return "IVI",
-                    _ => return "Display",
-                }
+                let display = match display_map.and_then(|map| map.get(display_id.as_str())) {
+                    Some(name) => Cow::Owned(name.clone()),
+                    None => match display_id.as_str() {
+                        "0" => Cow::Borrowed("Main"),
+                        "1" => Cow::Borrowed("Cluster"),
+                        "2" => Cow::Borrowed("IVI"),
+                        _ => Cow::Borrowed("Display"),
+                    },
+                };
+                return Some(DisplayMatch {
+                    display,
+                    source: "display_id",
+                    display_id: Some(display_id.as_str()),
+                });
             }
         }
     }
 
     // 태그 기반 분류
-    let tag_lower = tag.to_lowercase();
     if tag_lower.contains("cluster") {
-        return "Cluster";
+        return Some(DisplayMatch::new(Cow::Borrowed("Cluster"), "tag_cluster"));
     } else if tag_lower.contains("ivi") || tag_lower.contains("infotainment") {
-        return "IVI";
+        return Some(DisplayMatch::new(Cow::Borrowed("IVI"), "tag_ivi"));
     } else if tag_lower.contains("passenger") {
-        return "Passenger";
+        return Some(DisplayMatch::new(Cow::Borrowed("Passenger"), "tag_passenger"));
     }
 
-    "Main"
+    None
+}
+
+/// 태그/메시지의 디스플레이 분류 (파싱 없이 직접 호출)
+/// display_map: displayId 문자열 → 이름. 매핑에 없는 ID는 기본 매핑(0/1/2) 또는 "Display"
+/// pid가 있으면 태그+PID 조합 규칙도 적용
+/// detail=True면 이름 대신 {"display", "display_source", "display_id"(찾았을 때만)} dict (display_source 옵션과 같은 값)
+#[pyfunction]
+#[pyo3(name = "classify_display", signature = (tag, message, pid=None, display_map=None, detail=false))]
+fn py_classify_display(
+    py: Python<'_>,
+    tag: &str,
+    message: &str,
+    pid: Option<&str>,
+    display_map: Option<HashMap<String, String>>,
+    detail: bool,
+) -> PyResult<PyObject> {
+    let matched = match_display_detail(tag, pid.unwrap_or("-"), message, display_map.as_ref()).unwrap_or_default();
+    if !detail {
+        return Ok(matched.display.into_py(py));
+    }
+    let dict = PyDict::new_bound(py);
+    dict.set_item("display", matched.display)?;
+    dict.set_item("display_source", matched.source)?;
+    if let Some(display_id) = matched.display_id {
+        dict.set_item("display_id", display_id)?;
+    }
+    Ok(dict.into())
 }
 
 /// Python 모듈 정의
@@ -289,7 +5406,346 @@ fn logcat_parser_rs(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(parse_log_line, m)?)?;
     m.add_function(wrap_pyfunction!(parse_log_batch, m)?)?;
     m.add_function(wrap_pyfunction!(parse_log_file_chunk, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_from_offset, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_log_file_stats, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_file_mmap, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_file_queue, m)?)?;
+    m.add_class::<BatchIterator>()?;
+    m.add_class::<LogParser>()?;
+    m.add_class::<ParsedLog>()?;
+    m.add_function(wrap_pyfunction!(parse_batch_numeric, m)?)?;
+    m.add_function(wrap_pyfunction!(py_classify_display, m)?)?;
+    m.add_function(wrap_pyfunction!(level_severity, m)?)?;
+    m.add_function(wrap_pyfunction!(add_custom_format, m)?)?;
+    m.add_function(wrap_pyfunction!(clear_custom_formats, m)?)?;
     m.add_function(wrap_pyfunction!(count_file_lines, m)?)?;
+    m.add_function(wrap_pyfunction!(estimate_line_count, m)?)?;
+    m.add_function(wrap_pyfunction!(list_tags, m)?)?;
+    m.add_function(wrap_pyfunction!(detect_format, m)?)?;
+    m.add_function(wrap_pyfunction!(first_per_tag, m)?)?;
+    m.add_function(wrap_pyfunction!(compare_coverage, m)?)?;
+    m.add_function(wrap_pyfunction!(tag_transition_matrix, m)?)?;
+    m.add_function(wrap_pyfunction!(find_timestamp_clusters, m)?)?;
+    m.add_function(wrap_pyfunction!(find_events, m)?)?;
+    m.add_function(wrap_pyfunction!(index_dumpsys_sections, m)?)?;
+    m.add_function(wrap_pyfunction!(level_segments, m)?)?;
+    m.add_function(wrap_pyfunction!(group_by_uid, m)?)?;
+    m.add_function(wrap_pyfunction!(split_by_shard, m)?)?;
+    m.add_function(wrap_pyfunction!(split_by_tag_to_json, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_file_to_jsonl, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_file_to_csv, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_file_to_socket, m)?)?;
     m.add_function(wrap_pyfunction!(parse_file_streaming, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_bytes_streaming, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_file_search, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_windows, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_rotating_follow, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_file_follow, m)?)?;
+    m.add_function(wrap_pyfunction!(set_composite_display_rules, m)?)?;
+    m.add_function(wrap_pyfunction!(set_pid_map, m)?)?;
+    m.add_function(wrap_pyfunction!(load_pid_map_from_file, m)?)?;
+    m.add_function(wrap_pyfunction!(build_pid_map_from_log, m)?)?;
+    m.add_function(wrap_pyfunction!(add_transform, m)?)?;
+    m.add_function(wrap_pyfunction!(clear_transforms, m)?)?;
+    m.add_function(wrap_pyfunction!(set_regex_limits, m)?)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_line_inner_threadtime() {
+        let line = "01-15 10:23:45.123  1000  1234  5678 I ActivityManager: Start proc com.example";
+        assert_eq!(
+            parse_line_inner(line),
+            Some(LogFields {
+                line,
+                timestamp: "01-15 10:23:45.123",
+                level: "I",
+                pid: "1234",
+                tid: "5678",
+                tag: "ActivityManager",
+                message: "Start proc com.example",
+                uid: Some("1000"),
+                custom_format: None,
+            })
+        );
+    }
+
+    #[test]
+    fn parse_line_inner_threadtime_formats() {
+        let simple = parse_line_inner("01-15 10:23:45.123  1234  -  -  CarService: ready").unwrap();
+        assert_eq!((simple.level, simple.pid, simple.tid, simple.tag, simple.message), ("-", "1234", "-", "CarService", "ready"));
+
+        let complex = parse_line_inner("01-15 10:23:45.123  W  -  -  1234  5678  W  Vold: low space").unwrap();
+        assert_eq!((complex.level, complex.pid, complex.tid, complex.tag, complex.message), ("W", "1234", "5678", "Vold", "low space"));
+
+        let epoch = parse_line_inner("1700000000.123  1234  5678 E Camera: open failed").unwrap();
+        assert_eq!((epoch.timestamp, epoch.level, epoch.tag), ("1700000000.123", "E", "Camera"));
+    }
+
+    #[test]
+    fn parse_line_inner_untimed_formats() {
+        let brief = parse_line_inner("I/ActivityManager( 1234): Start proc").unwrap();
+        assert_eq!((brief.timestamp, brief.level, brief.pid, brief.tid, brief.tag, brief.message), ("-", "I", "1234", "-", "ActivityManager", "Start proc"));
+
+        let process = parse_line_inner("W( 1234) low memory  (LowMemoryKiller)").unwrap();
+        assert_eq!((process.level, process.pid, process.tag, process.message), ("W", "1234", "LowMemoryKiller", "low memory"));
+
+        let tag = parse_line_inner("D/Bluetooth: connected").unwrap();
+        assert_eq!((tag.pid, tag.tag, tag.message), ("-", "Bluetooth", "connected"));
+
+        let nested_colons = parse_line_inner("D/AudioFlinger::Thread: started").unwrap();
+        assert_eq!((nested_colons.tag, nested_colons.message), ("AudioFlinger::Thread", "started"));
+
+        let bare = parse_line_inner("D/Tag:msg").unwrap();
+        assert_eq!((bare.tag, bare.message), ("Tag", "msg"));
+    }
+
+    #[test]
+    fn split_tag_message_keeps_colons_in_message() {
+        assert_eq!(split_tag_message("Http: GET http://host:8080/api"), ("Http", "GET http://host:8080/api"));
+        assert_eq!(split_tag_message("Config: mode:fast retries:3"), ("Config", "mode:fast retries:3"));
+        assert_eq!(split_tag_message("Alarm: fired at 12:34:56"), ("Alarm", "fired at 12:34:56"));
+        assert_eq!(split_tag_message("AudioFlinger::Thread: started"), ("AudioFlinger::Thread", "started"));
+    }
+
+    #[test]
+    fn split_tag_message_empty_message_and_no_separator() {
+        assert_eq!(split_tag_message("vold:"), ("vold", ""));
+        assert_eq!(split_tag_message("vold:  "), ("vold", ""));
+        assert_eq!(split_tag_message("no separator here"), ("", "no separator here"));
+        assert_eq!(split_tag_message("Tag:msg"), ("Tag", "msg"));
+        assert_eq!(split_tag_message(""), ("", ""));
+    }
+
+    #[test]
+    fn match_log_fields_in_splits_at_first_separator() {
+        let url = match_log_fields_in("01-15 10:23:45.123  1000  1234  5678 I Http: GET http://host:8080/api", LogFormats::ALL).unwrap();
+        assert_eq!((url.tag, url.message), ("Http", "GET http://host:8080/api"));
+
+        let time = match_log_fields_in("01-15 10:23:45.123  1234  -  -  Alarm: fired at 12:34:56", LogFormats::ALL).unwrap();
+        assert_eq!((time.tag, time.message), ("Alarm", "fired at 12:34:56"));
+
+        let empty = match_log_fields_in("01-15 10:23:45.123  1000  1234  5678 I vold:", LogFormats::ALL).unwrap();
+        assert_eq!((empty.tag, empty.message), ("vold", ""));
+
+        let bare = match_log_fields_in("01-15 10:23:45.123  1234  -  -  Tag:value only", LogFormats::ALL).unwrap();
+        assert_eq!((bare.tag, bare.message), ("Tag", "value only"));
+
+        let untagged = match_log_fields_in("01-15 10:23:45.123  1234  -  -  no separator", LogFormats::ALL).unwrap();
+        assert_eq!((untagged.tag, untagged.message), ("", "no separator"));
+    }
+
+    #[test]
+    fn normalize_carriage_returns_handles_crlf() {
+        assert_eq!(normalize_carriage_returns("boot completed\r"), "boot completed");
+        assert_eq!(normalize_carriage_returns("line one\r\nline two\r\n\r"), "line one\nline two\n");
+        assert_eq!(normalize_carriage_returns("progress 10%\rprogress 20%"), "progress 10%\nprogress 20%");
+        assert!(matches!(normalize_carriage_returns("plain message"), Cow::Borrowed("plain message")));
+    }
+
+    #[test]
+    fn parse_line_inner_ignores_trailing_cr() {
+        let fields = parse_line_inner("01-15 10:23:45.123  1000  1234  5678 I CarService: ready\r").unwrap();
+        assert_eq!((fields.tag, fields.message), ("CarService", "ready"));
+    }
+
+    #[test]
+    fn nested_fields_ignores_plain_messages_with_slash() {
+        assert_eq!(nested_fields("I/O error: disk full"), None);
+        assert_eq!(nested_fields("W(  12) looks like process (Tag)"), None);
+
+        let threadtime = nested_fields("01-15 10:23:45.123  1000  1234  5678 E Camera: open failed").unwrap();
+        assert_eq!((threadtime.level, threadtime.tag, threadtime.message), ("E", "Camera", "open failed"));
+
+        let brief = nested_fields("proxy: I/Camera( 1234): open failed").unwrap();
+        assert_eq!((brief.level, brief.pid, brief.tag, brief.message), ("I", "1234", "Camera", "open failed"));
+    }
+
+    #[test]
+    fn parse_line_inner_rejects_other_lines() {
+        assert_eq!(parse_line_inner(""), None);
+        assert_eq!(parse_line_inner("   "), None);
+        assert_eq!(parse_line_inner("--------- beginning of main"), None);
+    }
+
+    /// 테스트마다 겹치지 않는 임시 파일 경로
+    fn temp_log_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("logcat_parser_rs_{}_{}.txt", std::process::id(), name))
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    fn read_all(followed: &mut FollowedFile, partial: &mut Vec<u8>) -> Vec<String> {
+        let (mut lines, mut errors) = (Vec::new(), Vec::new());
+        followed.read_available(partial, &mut lines, &mut errors, 0, None);
+        assert!(errors.is_empty());
+        lines
+    }
+
+    #[test]
+    fn follow_continues_across_rotation_without_gaps_or_duplicates() {
+        let path = temp_log_path("rotation");
+        let rotated = format!("{}.1", path);
+        std::fs::write(&path, "line 1\nline 2\nline 3 (partial").unwrap();
+
+        let mut followed = FollowedFile::open(&path).unwrap();
+        let mut partial = Vec::new();
+        assert_eq!(read_all(&mut followed, &mut partial), ["line 1", "line 2"]);
+        assert!(matches!(check_rotation(&path, &followed), RotationState::Unchanged));
+
+        // 쓰던 줄을 마저 쓰고 log.txt → log.txt.1, 새 log.txt 생성
+        let mut old = std::fs::OpenOptions::new().append(true).open(&path).unwrap();
+        old.write_all(b")\nline 4\n").unwrap();
+        drop(old);
+        std::fs::rename(&path, &rotated).unwrap();
+        std::fs::write(&path, "line 5\n").unwrap();
+
+        if cfg!(unix) {
+            assert!(matches!(check_rotation(&path, &followed), RotationState::Rotated));
+        }
+        // 이전 파일의 나머지를 끝까지 읽은 뒤 새 파일로 전환
+        assert_eq!(read_all(&mut followed, &mut partial), ["line 3 (partial)", "line 4"]);
+        let mut followed = FollowedFile::open(&path).unwrap();
+        assert_eq!(read_all(&mut followed, &mut partial), ["line 5"]);
+        assert!(matches!(check_rotation(&path, &followed), RotationState::Unchanged));
+
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(&rotated).unwrap();
+    }
+
+    #[test]
+    fn follow_detects_copytruncate() {
+        let path = temp_log_path("truncate");
+        std::fs::write(&path, "line 1\nline 2\n").unwrap();
+
+        let mut followed = FollowedFile::open(&path).unwrap();
+        let mut partial = Vec::new();
+        assert_eq!(read_all(&mut followed, &mut partial).len(), 2);
+
+        std::fs::write(&path, "new\n").unwrap();
+        let state = check_rotation(&path, &followed);
+        if cfg!(unix) {
+            assert!(matches!(state, RotationState::Truncated));
+        } else {
+            assert!(matches!(state, RotationState::Rotated));
+        }
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn detect_init_event_real_samples() {
+        let start = parse_line_inner("01-01 00:00:04.512     0     0     1 I init    : starting service 'vold'...").unwrap();
+        let event = detect_init_event(start.tag, start.message).unwrap();
+        assert_eq!((event.service, event.state, event.service_pid), ("vold", "starting", None));
+
+        let exited = parse_line_inner("01-01 00:00:21.307     0     0     1 I init    : Service 'bootanim' (pid 612) exited with status 0").unwrap();
+        let event = detect_init_event(exited.tag, exited.message).unwrap();
+        assert_eq!((event.service, event.state, event.service_pid), ("bootanim", "exited", Some("612")));
+        assert_eq!((event.exit_status, event.signal), (Some(0), None));
+
+        let signaled = detect_init_event("init", "Service 'adbd' (pid 433) received signal 9").unwrap();
+        assert_eq!((signaled.state, signaled.exit_status, signaled.signal), ("signaled", None, Some(9)));
+        let killed = detect_init_event("init", "Service 'zygote' (pid 301) killed by signal 11").unwrap();
+        assert_eq!((killed.service, killed.signal), ("zygote", Some(11)));
+    }
+
+    #[test]
+    fn detect_init_event_ignores_other_lines() {
+        assert!(detect_init_event("ActivityManager", "starting service 'vold'...").is_none());
+        assert!(detect_init_event("init", "Parsing file /system/etc/init/hw/init.rc...").is_none());
+    }
+
+    #[test]
+    fn detect_binder_failure_representative_lines() {
+        let ipc = parse_line_inner(
+            "01-15 10:23:45.123  1000  1234  1250 E IPCThreadState: Binder transaction failure. id: 12, BR_*: 29201, error: -28 (No space left on device)",
+        )
+        .unwrap();
+        assert_eq!(detect_binder_failure(ipc.message), Some(Some(-28)));
+
+        assert_eq!(detect_binder_failure("1234:1250 transaction failed 29189/-22, size 96-0 line 3008"), Some(Some(-22)));
+        assert_eq!(detect_binder_failure("failed to transact: -32"), Some(Some(-32)));
+        assert_eq!(detect_binder_failure("!!! FAILED BINDER TRANSACTION !!!  (parcel size = 1048676)"), Some(None));
+        assert_eq!(detect_binder_failure("getService returned status: FAILED_TRANSACTION"), Some(None));
+    }
+
+    #[test]
+    fn detect_binder_failure_ignores_other_lines() {
+        assert_eq!(detect_binder_failure("binder: 1234:1250 BC_TRANSACTION 29189 -> 433:433 node 12"), None);
+        assert_eq!(detect_binder_failure("transaction completed in 3ms"), None);
+    }
+
+    #[test]
+    fn strip_trailing_control_null_terminated_sample() {
+        let fields = parse_line_inner("01-15 10:23:45.123  1000  1234  5678 I VehicleHal: property updated\0").unwrap();
+        assert_eq!(fields.message, "property updated\0");
+        assert_eq!(strip_trailing_control(fields.message), "property updated");
+
+        assert_eq!(strip_trailing_control("eof marker\x04\0\0"), "eof marker");
+        assert_eq!(strip_trailing_control("\0"), "");
+    }
+
+    #[test]
+    fn strip_trailing_control_keeps_tabs_and_inner_bytes() {
+        assert_eq!(strip_trailing_control("col1\tcol2\t"), "col1\tcol2\t");
+        assert_eq!(strip_trailing_control("a\0b"), "a\0b");
+        assert_eq!(strip_trailing_control("plain"), "plain");
+    }
+
+    #[test]
+    fn detect_jit_event_art_startup_lines() {
+        let verify = parse_line_inner(
+            "01-15 10:23:45.123 10123  2345  2345 W com.example: Verification of void com.example.MainActivity.onCreate(android.os.Bundle) took 123.456ms",
+        )
+        .unwrap();
+        let event = detect_jit_event(verify.message).unwrap();
+        assert_eq!((event.kind, event.target), ("verification", "void com.example.MainActivity.onCreate(android.os.Bundle)"));
+        assert_eq!(event.duration_ms, Some(123.456));
+
+        let compiled = detect_jit_event("JIT compiled void com.example.Worker.run() in 1500us").unwrap();
+        assert_eq!((compiled.kind, compiled.target, compiled.duration_ms), ("jit_compile", "void com.example.Worker.run()", Some(1.5)));
+
+        let untimed = detect_jit_event("JIT compiled int com.example.Math.add(int, int)").unwrap();
+        assert_eq!((untimed.target, untimed.duration_ms), ("int com.example.Math.add(int, int)", None));
+
+        let lock = detect_jit_event("Class com.example.Legacy failed lock verification and will run slower.").unwrap();
+        assert_eq!((lock.kind, lock.target, lock.duration_ms), ("lock_verification", "com.example.Legacy", None));
+    }
+
+    #[test]
+    fn detect_jit_event_ignores_other_lines() {
+        assert!(detect_jit_event("Background concurrent copying GC freed 12345(1MB) AllocSpace objects").is_none());
+        assert!(detect_jit_event("Verification of signature passed").is_none());
+    }
+
+    #[test]
+    fn timestamp_to_nanos_orders_close_nsec_lines() {
+        let first = parse_line_inner("01-15 10:23:45.123456789  1000  1234  5678 I Sensor: sample").unwrap();
+        let second = parse_line_inner("01-15 10:23:45.123456790  1000  1234  5678 I Sensor: sample").unwrap();
+        assert_eq!(first.timestamp, "01-15 10:23:45.123456789");
+        assert_eq!(first.message, "sample");
+
+        let (a, b) = (timestamp_to_nanos(first.timestamp).unwrap(), timestamp_to_nanos(second.timestamp).unwrap());
+        assert_eq!(b - a, 1);
+        // 밀리초로는 구분되지 않음
+        assert_eq!(timestamp_to_millis(first.timestamp), timestamp_to_millis(second.timestamp));
+    }
+
+    #[test]
+    fn timestamp_to_nanos_scales_each_precision() {
+        let millis = timestamp_to_nanos("01-01 00:00:01.123").unwrap();
+        let micros = timestamp_to_nanos("01-01 00:00:01.123456").unwrap();
+        let nanos = timestamp_to_nanos("01-01 00:00:01.123456789").unwrap();
+        assert_eq!(millis, 1_123_000_000);
+        assert_eq!(micros, 1_123_456_000);
+        assert_eq!(nanos, 1_123_456_789);
+        assert!(millis < micros && micros < nanos);
+        assert!(nanos < timestamp_to_nanos("01-01 00:00:01.124").unwrap());
+    }
+}